@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Parser;
+use json_editor_engine::{
+    self as engine, load_project, parse_cached, ErrorEnum, ParseOutput, RegexCache, ScenItem,
+};
+use serde::Serialize;
+
+#[derive(Parser)]
+#[command(
+    version,
+    about = "Применяет сценарии из файла проекта к документам и выводит результат в JSON"
+)]
+struct Args {
+    #[arg(help = "Файл проекта или список сценариев (JSON)")]
+    scenario: PathBuf,
+    #[arg(
+        required = true,
+        help = "Документы: *.json читаются как проект (file_name и text), остальные — как обычный текст"
+    )]
+    documents: Vec<PathBuf>,
+    #[arg(
+        short,
+        long,
+        help = "Каталог для результатов: по файлу <имя документа>.json на документ вместо вывода в stdout"
+    )]
+    out_dir: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct DocumentResult {
+    document: String,
+    output: ParseOutput,
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Не удалось прочитать {}: {}", path.display(), e))
+}
+
+fn load_scens(path: &Path) -> Result<Vec<ScenItem>, String> {
+    load_project(&read(path)?)
+        .map(|(project, _)| project.into_scens())
+        .map_err(|e| format!("Не удалось загрузить {}: {}", path.display(), e))
+}
+
+fn load_document(path: &Path) -> Result<(String, String), String> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    engine::load_document(&name, read(path)?)
+}
+
+fn output_name(document: &Path) -> String {
+    let name = document
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!("{}.json", name)
+}
+
+// Результаты называются по имени документа без каталога, поэтому a/doc.txt и b/doc.txt
+// записались бы в один файл; такой запуск отклоняется до разбора
+fn check_output_names(documents: &[PathBuf]) -> Result<(), String> {
+    let mut seen: HashMap<String, &Path> = HashMap::new();
+    for document in documents {
+        if let Some(other) = seen.insert(output_name(document), document) {
+            return Err(format!(
+                "Результаты {} и {} попали бы в один файл {}",
+                other.display(),
+                document.display(),
+                output_name(document)
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn write_results(dir: &Path, results: &[DocumentResult]) -> Result<(), String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Не удалось создать каталог {}: {}", dir.display(), e))?;
+    for result in results {
+        let path = dir.join(output_name(Path::new(&result.document)));
+        let data = serde_json::to_string_pretty(&result.output).map_err(|e| e.to_string())?;
+        fs::write(&path, data)
+            .map_err(|e| format!("Не удалось записать {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Summary {
+    // хотя бы в одном документе есть ошибки уровня Error
+    errors: bool,
+    // хотя бы один документ не удалось прочитать
+    unreadable: bool,
+}
+
+// Документ, который не удалось прочитать, пропускается; остальные разбираются
+fn run(args: &Args) -> Result<Summary, String> {
+    if args.out_dir.is_some() {
+        check_output_names(&args.documents)?;
+    }
+    let scens = load_scens(&args.scenario)?;
+    let mut cache = RegexCache::new();
+    let mut results = vec![];
+    let mut summary = Summary::default();
+    for path in &args.documents {
+        let (file_name, text) = match load_document(path) {
+            Ok(document) => document,
+            Err(e) => {
+                eprintln!("{}", e);
+                summary.unreadable = true;
+                continue;
+            }
+        };
+        let output = parse_cached(file_name, text, scens.clone(), &mut cache);
+        let errors = output
+            .2
+            .iter()
+            .filter(|e| e.type_error == ErrorEnum::Error)
+            .count();
+        if errors > 0 {
+            eprintln!("{}: ошибок {}", path.display(), errors);
+            summary.errors = true;
+        }
+        results.push(DocumentResult {
+            document: path.display().to_string(),
+            output,
+        });
+    }
+    match &args.out_dir {
+        Some(dir) => write_results(dir, &results)?,
+        None => println!(
+            "{}",
+            serde_json::to_string_pretty(&results).map_err(|e| e.to_string())?
+        ),
+    }
+    Ok(summary)
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(&args) {
+        Ok(Summary { unreadable: true, .. }) => ExitCode::from(2),
+        Ok(Summary { errors: true, .. }) => ExitCode::from(1),
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use regex::Regex;
+use std::collections::HashMap;
+
+// Больше выражений не храним: при наборе на лету каждое нажатие даёт новое выражение
+const MAX_CACHED: usize = 256;
+
+// Скомпилированные выражения по тексту выражения, чтобы не компилировать
+// неизменившиеся сценарии при каждом разборе
+#[derive(Default)]
+pub struct RegexCache {
+    compiled: HashMap<String, Result<Regex, regex::Error>>,
+}
+
+impl RegexCache {
+    pub fn new() -> RegexCache {
+        RegexCache::default()
+    }
+
+    pub fn get(&mut self, pattern: &str) -> Result<Regex, regex::Error> {
+        if let Some(compiled) = self.compiled.get(pattern) {
+            return compiled.clone();
+        }
+        if self.compiled.len() >= MAX_CACHED {
+            self.compiled.clear();
+        }
+        let compiled = Regex::new(pattern);
+        self.compiled.insert(pattern.to_string(), compiled.clone());
+        compiled
+    }
+}
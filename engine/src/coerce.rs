@@ -0,0 +1,204 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use serde_json::{Number, Value};
+
+use crate::parse::ValueType;
+
+const DATE_FORMATS: [&str; 6] = [
+    "%d.%m.%Y", "%d.%m.%y", "%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y", "%d %m %Y",
+];
+
+const DATETIME_FORMATS: [&str; 8] = [
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d.%m.%y %H:%M:%S",
+    "%d.%m.%y %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%d %m %Y %H:%M",
+];
+
+// Начала названий месяцев: покрывают и именительный, и родительный падеж, и сокращения
+const MONTHS: [(&str, &str); 12] = [
+    ("янв", "1"),
+    ("фев", "2"),
+    ("мар", "3"),
+    ("апр", "4"),
+    ("ма", "5"),
+    ("июн", "6"),
+    ("июл", "7"),
+    ("авг", "8"),
+    ("сен", "9"),
+    ("окт", "10"),
+    ("ноя", "11"),
+    ("дек", "12"),
+];
+
+pub fn coerce(value: &str, value_type: &ValueType, format: Option<&str>) -> Result<Value, String> {
+    let trimmed = value.trim();
+    match value_type {
+        ValueType::String => Ok(Value::String(value.to_string())),
+        ValueType::Integer => normalize_number(trimmed)
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|e| e.to_string()),
+        ValueType::Decimal => {
+            let f = normalize_number(trimmed)
+                .parse::<f64>()
+                .map_err(|e| e.to_string())?;
+            Number::from_f64(f)
+                .map(Value::Number)
+                .ok_or_else(|| "число не может быть представлено в JSON".to_string())
+        }
+        ValueType::Boolean => match trimmed.to_lowercase().as_str() {
+            "true" | "1" | "да" | "yes" | "+" | "истина" => Ok(Value::Bool(true)),
+            "false" | "0" | "нет" | "no" | "-" | "ложь" => Ok(Value::Bool(false)),
+            _ => Err("ожидается да/нет".to_string()),
+        },
+        ValueType::Date => {
+            let date = parse_with(trimmed, format, &DATE_FORMATS, |v, f| {
+                NaiveDate::parse_from_str(v, f)
+                    .ok()
+                    .filter(|d| full_year(d.year(), f))
+            })?;
+            Ok(Value::String(date.format("%Y-%m-%d").to_string()))
+        }
+        ValueType::DateTime => {
+            let datetime = parse_with(trimmed, format, &DATETIME_FORMATS, |v, f| {
+                NaiveDateTime::parse_from_str(v, f)
+                    .ok()
+                    .filter(|d| full_year(d.year(), f))
+            })?;
+            Ok(Value::String(
+                datetime.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            ))
+        }
+    }
+}
+
+// chrono принимает по %Y и две цифры: "12.03.24" превратился бы в 0024 год,
+// поэтому такие годы отбрасываются и очередь доходит до формата с %y
+fn full_year(year: i32, format: &str) -> bool {
+    year >= 100 || !format.contains("%Y")
+}
+
+// "1 234,56" -> "1234.56", "1,234.56" -> "1234.56"
+fn normalize_number(value: &str) -> String {
+    let mut s: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\'' && *c != '\u{202f}')
+        .collect();
+    match (s.rfind(','), s.rfind('.')) {
+        (Some(comma), Some(dot)) if comma > dot => {
+            s = s.replace('.', "").replace(',', ".");
+        }
+        (Some(_), Some(_)) => {
+            s = s.replace(',', "");
+        }
+        (Some(_), None) => {
+            s = s.replace(',', ".");
+        }
+        _ => {}
+    }
+    s
+}
+
+// "12 марта 2024 г." -> "12 3 2024"
+fn normalize_date(value: &str) -> String {
+    let lower = value.to_lowercase();
+    let mut words = vec![];
+    for word in lower.split_whitespace() {
+        let word = word.trim_end_matches(',');
+        if matches!(word, "г." | "г" | "года" | "год") {
+            continue;
+        }
+        let word = word.strip_suffix("г.").unwrap_or(word);
+        let is_alpha = word.chars().all(|c| c.is_alphabetic() || c == '.');
+        match MONTHS
+            .iter()
+            .find(|(prefix, _)| is_alpha && word.starts_with(prefix))
+        {
+            Some((_, number)) => words.push(number.to_string()),
+            None => words.push(word.to_string()),
+        }
+    }
+    words.join(" ")
+}
+
+fn parse_with<T>(
+    value: &str,
+    format: Option<&str>,
+    defaults: &[&str],
+    parse: impl Fn(&str, &str) -> Option<T>,
+) -> Result<T, String> {
+    let normalized = normalize_date(value);
+    let formats: Vec<String> = match format {
+        Some(f) if !f.trim().is_empty() => {
+            vec![f.to_string(), f.replace("%B", "%m").replace("%b", "%m")]
+        }
+        _ => defaults.iter().map(|f| f.to_string()).collect(),
+    };
+    for f in &formats {
+        if let Some(v) = parse(value, f).or_else(|| parse(&normalized, f)) {
+            return Ok(v);
+        }
+    }
+    Err(format!(
+        "не подходит ни один формат: {}",
+        formats.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn decimal_with_space_and_comma() {
+        assert_eq!(coerce("1 234,56", &ValueType::Decimal, None), Ok(json!(1234.56)));
+        assert_eq!(coerce("1,234.56", &ValueType::Decimal, None), Ok(json!(1234.56)));
+        assert_eq!(coerce("1 234", &ValueType::Integer, None), Ok(json!(1234)));
+    }
+
+    #[test]
+    fn date_with_month_name() {
+        assert_eq!(
+            coerce("12 марта 2024 г.", &ValueType::Date, None),
+            Ok(json!("2024-03-12"))
+        );
+        assert_eq!(
+            coerce("1 мая 2024", &ValueType::Date, None),
+            Ok(json!("2024-05-01"))
+        );
+        assert_eq!(
+            coerce("12.03.2024", &ValueType::Date, None),
+            Ok(json!("2024-03-12"))
+        );
+    }
+
+    #[test]
+    fn date_with_two_digit_year() {
+        assert_eq!(
+            coerce("12.03.24", &ValueType::Date, None),
+            Ok(json!("2024-03-12"))
+        );
+        assert_eq!(
+            coerce("12.03.24", &ValueType::Date, Some("%d.%m.%y")),
+            Ok(json!("2024-03-12"))
+        );
+        assert!(coerce("12.03.24", &ValueType::Date, Some("%d.%m.%Y")).is_err());
+        assert_eq!(
+            coerce("12.03.24 10:30", &ValueType::DateTime, None),
+            Ok(json!("2024-03-12T10:30:00"))
+        );
+    }
+
+    #[test]
+    fn invalid_values_are_errors() {
+        assert!(coerce("abc", &ValueType::Decimal, None).is_err());
+        assert!(coerce("может быть", &ValueType::Boolean, None).is_err());
+        assert!(coerce("32.13.2024", &ValueType::Date, None).is_err());
+    }
+}
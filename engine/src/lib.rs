@@ -0,0 +1,13 @@
+pub mod cache;
+pub mod coerce;
+pub mod lint;
+pub mod migrate;
+pub mod parse;
+pub mod schema;
+pub mod transform;
+
+pub use cache::RegexCache;
+pub use parse::{
+    load_document, load_project, parse, parse_cached, ErrorEnum, ErrorItem, OutputItem, OutputItemTabls,
+    OutputValue, ParseJob, ParseOutput, ProjectFile, Scen, ScenItem,
+};
@@ -0,0 +1,249 @@
+use regex::Regex;
+
+use crate::cache::RegexCache;
+use crate::parse::{ErrorCode, ErrorEnum, ErrorItem, ScenItem};
+use crate::transform::Pipeline;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegexSyntaxError {
+    pub message: String,
+    // смещения в символах внутри выражения
+    pub start: usize,
+    pub end: usize,
+}
+
+// Текст и место ошибки компиляции выражения; None, если выражение корректно
+pub fn regex_syntax_error(regex: &str) -> Option<RegexSyntaxError> {
+    let (message, span) = match regex_syntax::Parser::new().parse(regex) {
+        Ok(_) => (Regex::new(regex).err()?.to_string(), None),
+        Err(regex_syntax::Error::Parse(e)) => (e.kind().to_string(), Some(*e.span())),
+        Err(regex_syntax::Error::Translate(e)) => (e.kind().to_string(), Some(*e.span())),
+        Err(e) => (e.to_string(), None),
+    };
+    let (start, end) = match span {
+        Some(s) => (
+            regex[..s.start.offset].chars().count(),
+            regex[..s.end.offset].chars().count(),
+        ),
+        None => (0, 0),
+    };
+    Some(RegexSyntaxError {
+        message,
+        start,
+        end,
+    })
+}
+
+// Именованные группы выражения по порядку; None, если выражение не компилируется
+pub fn capture_groups(regex: &str) -> Option<Vec<String>> {
+    let re = Regex::new(regex).ok()?;
+    Some(re.capture_names().flatten().map(|n| n.to_string()).collect())
+}
+
+// Проверяет сценарий без текста документа: выражения, имена групп, маску таблицы
+pub fn lint_item(index: usize, r: &ScenItem) -> Vec<ErrorItem> {
+    let mut out_error_vec: Vec<ErrorItem> = vec![];
+    let mut push = |code: ErrorCode,
+                    type_error: ErrorEnum,
+                    message: String,
+                    field: Option<&str>,
+                    detail: Option<String>| {
+        out_error_vec.push(ErrorItem {
+            message,
+            type_error,
+            code,
+            scen_index: index,
+            field: field.map(|f| f.to_string()),
+            detail,
+        });
+    };
+
+    for (position, name) in r.names.iter().enumerate() {
+        if name.trim().is_empty() {
+            push(
+                ErrorCode::EmptyName,
+                ErrorEnum::Warning,
+                format!("Пустое имя на позиции {} (лишняя запятая?)", position + 1),
+                None,
+                None,
+            );
+        }
+    }
+
+    if r.table {
+        match r.table_mask.as_deref().filter(|tm| !tm.trim().is_empty()) {
+            None => push(
+                ErrorCode::MissingTableMask,
+                ErrorEnum::Error,
+                "Отсутствует регулярное вырожение для поиска таблицы".to_string(),
+                None,
+                None,
+            ),
+            Some(tm) => {
+                if let Err(e) = Regex::new(tm) {
+                    push(
+                        ErrorCode::InvalidTableMask,
+                        ErrorEnum::Error,
+                        format!("Есть ошибки в регулярном выражении: {}", tm),
+                        None,
+                        Some(e.to_string()),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Err((pattern, e)) = Pipeline::new(&r.transforms, &mut RegexCache::new()) {
+        push(
+            ErrorCode::InvalidReplaceRegex,
+            ErrorEnum::Error,
+            format!("Есть ошибки в регулярном выражении замены: {}", pattern),
+            None,
+            Some(e.to_string()),
+        );
+    }
+
+    for f in &r.fields {
+        if !r.names.contains(&f.name) {
+            push(
+                ErrorCode::UnknownField,
+                ErrorEnum::Warning,
+                format!(
+                    "Настройки заданы для имени, которого нет в списке: {}",
+                    f.name
+                ),
+                Some(&f.name),
+                None,
+            );
+        }
+    }
+
+    let mut all_groups: Vec<&str> = vec![];
+    let chain: Vec<(usize, &String, Regex)> = r
+        .regexes()
+        .enumerate()
+        .filter_map(|(alternative, pattern)| match Regex::new(pattern) {
+            Ok(re) => Some((alternative, pattern, re)),
+            Err(e) => {
+                push(
+                    ErrorCode::InvalidRegex,
+                    ErrorEnum::Error,
+                    format!("Есть ошибки в регулярном выражении: {}", pattern),
+                    None,
+                    Some(e.to_string()),
+                );
+                None
+            }
+        })
+        .collect();
+    for (alternative, pattern, re) in &chain {
+        let groups: Vec<&str> = re.capture_names().flatten().collect();
+        for name in r.names.iter().filter(|n| !n.trim().is_empty()) {
+            if !groups.contains(&name.as_str()) {
+                let message = if *alternative == 0 {
+                    format!("В выражении нет группы с именем: {}", name)
+                } else {
+                    format!(
+                        "В запасном выражении {} нет группы с именем: {}",
+                        pattern, name
+                    )
+                };
+                push(
+                    ErrorCode::UnknownGroup,
+                    ErrorEnum::Error,
+                    message,
+                    Some(name),
+                    Some(format!("Группы выражения: {}", groups.join(", "))),
+                );
+            }
+        }
+        for group in groups {
+            if !all_groups.contains(&group) {
+                all_groups.push(group);
+            }
+        }
+    }
+    for group in all_groups.iter().filter(|g| !r.names.iter().any(|n| n == *g)) {
+        push(
+            ErrorCode::UnusedGroup,
+            ErrorEnum::Info,
+            format!("Группа не используется: {}", group),
+            Some(group),
+            None,
+        );
+    }
+    out_error_vec
+}
+
+pub fn lint(scens: &[ScenItem]) -> Vec<ErrorItem> {
+    scens
+        .iter()
+        .enumerate()
+        .flat_map(|(index, r)| lint_item(index, r))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(r: &ScenItem) -> Vec<ErrorCode> {
+        lint_item(0, r).into_iter().map(|e| e.code).collect()
+    }
+
+    #[test]
+    fn clean_item_has_no_findings() {
+        let r = ScenItem {
+            regex: r"(?P<a>\d+)".to_string(),
+            names: vec!["a".to_string()],
+            ..Default::default()
+        };
+        assert!(codes(&r).is_empty());
+    }
+
+    #[test]
+    fn reports_names_and_groups() {
+        let r = ScenItem {
+            regex: r"(?P<a>\d+)(?P<b>\w)".to_string(),
+            names: vec!["a".to_string(), "".to_string(), "c".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            codes(&r),
+            vec![
+                ErrorCode::EmptyName,
+                ErrorCode::UnknownGroup,
+                ErrorCode::UnusedGroup
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_table_mask_and_regex() {
+        let r = ScenItem {
+            regex: "(".to_string(),
+            table: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            codes(&r),
+            vec![ErrorCode::MissingTableMask, ErrorCode::InvalidRegex]
+        );
+    }
+
+    #[test]
+    fn checks_every_alternative() {
+        let r = ScenItem {
+            regex: r"(?P<a>\d+)".to_string(),
+            alternatives: vec![r"(?P<b>\d+)".to_string()],
+            names: vec!["a".to_string()],
+            ..Default::default()
+        };
+        let errs = lint_item(0, &r);
+        assert_eq!(errs.len(), 2);
+        assert_eq!(errs[0].code, ErrorCode::UnknownGroup);
+        assert!(errs[0].message.contains(r"(?P<b>\d+)"));
+        assert_eq!(errs[1].code, ErrorCode::UnusedGroup);
+        assert_eq!(errs[1].field.as_deref(), Some("b"));
+    }
+}
@@ -0,0 +1,187 @@
+use serde_json::{json, Map, Value};
+
+// Версия формата файла проекта. Файлы без поля version считаются версией 1
+pub const FORMAT_VERSION: u32 = 3;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationReport {
+    pub from: u32,
+    pub to: u32,
+    pub changes: Vec<String>,
+}
+
+type Migration = fn(&mut Map<String, Value>, &mut Vec<String>);
+
+// MIGRATIONS[i] переводит проект из версии i + 1 в версию i + 2
+const MIGRATIONS: [Migration; 2] = [v1_to_v2, v2_to_v3];
+
+// Числа, не помещающиеся в u32, считаются заведомо более новой версией
+pub fn version_of(project: &Value) -> u32 {
+    project
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .map(|v| u32::try_from(v).unwrap_or(u32::MAX))
+        .unwrap_or(1)
+}
+
+pub fn migrate(project: &mut Value) -> Result<MigrationReport, String> {
+    let from = version_of(project);
+    if from == 0 {
+        return Err("Некорректная версия формата: 0 (версии начинаются с 1)".to_string());
+    }
+    if from > FORMAT_VERSION {
+        return Err(format!(
+            "Файл создан более новой версией редактора (формат {}, поддерживается до {})",
+            from, FORMAT_VERSION
+        ));
+    }
+    let Some(object) = project.as_object_mut() else {
+        return Err("Ожидается объект проекта".to_string());
+    };
+    let mut changes = vec![];
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(from as usize - 1) {
+        migration(object, &mut changes);
+        object.insert("version".to_string(), json!(i as u32 + 2));
+    }
+    Ok(MigrationReport {
+        from,
+        to: FORMAT_VERSION,
+        changes,
+    })
+}
+
+fn v1_to_v2(project: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    if !project.contains_key("meta") {
+        project.insert("meta".to_string(), json!({}));
+        changes.push("Добавлено описание проекта (meta)".to_string());
+    }
+    let Some(Value::Array(items)) = project.get_mut("scen") else {
+        return;
+    };
+    for (index, item) in items.iter_mut().enumerate() {
+        let Some(item) = item.as_object_mut() else {
+            continue;
+        };
+        // пустые имена появлялись из split(',') при пустом поле «Имена»
+        if let Some(Value::Array(names)) = item.get_mut("names") {
+            let before = names.clone();
+            names.retain(|n| n.as_str().is_some_and(|s| !s.trim().is_empty()));
+            for n in names.iter_mut() {
+                if let Some(s) = n.as_str() {
+                    *n = json!(s.trim());
+                }
+            }
+            if *names != before {
+                changes.push(format!(
+                    "Сценарий {}: убраны пустые имена и пробелы вокруг имён",
+                    index
+                ));
+            }
+        }
+        let table = item.get("table").and_then(|t| t.as_bool()).unwrap_or(false);
+        if !table && item.get("table_mask").is_some_and(|m| !m.is_null()) {
+            item.insert("table_mask".to_string(), Value::Null);
+            changes.push(format!(
+                "Сценарий {}: убрано выражение таблицы у сценария без таблицы",
+                index
+            ));
+        }
+        let mut added = vec![];
+        for (key, default) in [
+            ("mode", json!("First")),
+            ("transforms", json!([])),
+            ("fields", json!([])),
+        ] {
+            if !item.contains_key(key) {
+                item.insert(key.to_string(), default);
+                added.push(key);
+            }
+        }
+        if !added.is_empty() {
+            changes.push(format!(
+                "Сценарий {}: добавлены поля {} со значениями по умолчанию",
+                index,
+                added.join(", ")
+            ));
+        }
+    }
+}
+
+fn v2_to_v3(project: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    let Some(Value::Array(items)) = project.get_mut("scen") else {
+        return;
+    };
+    let mut count = 0;
+    for item in items.iter_mut() {
+        let Some(item) = item.as_object_mut() else {
+            continue;
+        };
+        if !item.contains_key("alternatives") {
+            item.insert("alternatives".to_string(), json!([]));
+            count += 1;
+        }
+    }
+    if count > 0 {
+        changes.push(format!(
+            "Добавлен пустой список запасных выражений в сценариях: {}",
+            count
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_v1_to_current() {
+        let mut project = json!({
+            "file_name": "a.txt",
+            "text": "b",
+            "scen": [
+                {"regex": "(?<x>\\d)", "names": [" x ", ""], "table": false, "table_mask": "t"}
+            ]
+        });
+        let report = migrate(&mut project).unwrap();
+        assert_eq!(report.from, 1);
+        assert_eq!(report.to, FORMAT_VERSION);
+        assert_eq!(project["version"], json!(FORMAT_VERSION));
+        assert_eq!(project["meta"], json!({}));
+        let item = &project["scen"][0];
+        assert_eq!(item["names"], json!(["x"]));
+        assert_eq!(item["table_mask"], Value::Null);
+        assert_eq!(item["mode"], json!("First"));
+        assert_eq!(item["transforms"], json!([]));
+        assert_eq!(item["fields"], json!([]));
+        assert_eq!(item["alternatives"], json!([]));
+    }
+
+    #[test]
+    fn current_version_is_unchanged() {
+        let mut project = json!({"version": FORMAT_VERSION, "meta": {}, "scen": []});
+        let before = project.clone();
+        let report = migrate(&mut project).unwrap();
+        assert!(report.changes.is_empty());
+        assert_eq!(project, before);
+    }
+
+    #[test]
+    fn rejects_version_zero() {
+        let mut project = json!({"version": 0, "file_name": "a", "text": "b"});
+        assert!(migrate(&mut project).is_err());
+    }
+
+    #[test]
+    fn rejects_future_version() {
+        let mut project = json!({"version": FORMAT_VERSION + 1});
+        assert!(migrate(&mut project).is_err());
+        let mut project = json!({"version": u64::from(u32::MAX) + 1});
+        assert!(migrate(&mut project).is_err());
+    }
+
+    #[test]
+    fn rejects_non_object() {
+        let mut project = json!("scen");
+        assert!(migrate(&mut project).is_err());
+    }
+}
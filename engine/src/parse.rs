@@ -0,0 +1,834 @@
+use regex::{Captures, Regex};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use crate::cache::RegexCache;
+use crate::coerce::coerce;
+use crate::migrate::{migrate, MigrationReport, FORMAT_VERSION};
+use crate::transform::{Pipeline, Transform};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq)]
+pub enum ErrorEnum {
+    Warning,
+    Error,
+    Info,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ProjectMeta {
+    pub description: String,
+    pub saved_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default)]
+pub struct Scen {
+    pub version: u32,
+    pub file_name: String,
+    pub text: String,
+    pub scen: Option<Vec<ScenItem>>,
+    pub meta: ProjectMeta,
+}
+
+pub enum ProjectFile {
+    Full(Scen),
+    // файл, где сохранён только массив сценариев
+    Scens(Vec<ScenItem>),
+}
+
+impl ProjectFile {
+    pub fn into_scens(self) -> Vec<ScenItem> {
+        match self {
+            ProjectFile::Full(scen) => scen.scen.unwrap_or_default(),
+            ProjectFile::Scens(scens) => scens,
+        }
+    }
+}
+
+pub fn load_project(text: &str) -> Result<(ProjectFile, MigrationReport), serde_json::Error> {
+    let value: Value = serde_json::from_str(text)?;
+    let bare = value.is_array();
+    let mut project = if bare {
+        json!({ "meta": {}, "scen": value })
+    } else {
+        value
+    };
+    let report = migrate(&mut project).map_err(<serde_json::Error as serde::de::Error>::custom)?;
+    if bare {
+        let scens = serde_json::from_value::<Vec<ScenItem>>(project["scen"].take())?;
+        return Ok((ProjectFile::Scens(scens), report));
+    }
+    // без миграции разбираем исходный текст, чтобы ошибка указывала строку и столбец
+    let scen = if report.from == FORMAT_VERSION {
+        serde_json::from_str::<Scen>(text)?
+    } else {
+        serde_json::from_value::<Scen>(project)?
+    };
+    Ok((ProjectFile::Full(scen), report))
+}
+
+// Документ для разбора: *.json читается как проект (берутся file_name и text),
+// остальные файлы — как обычный текст с именем файла. Возвращает имя файла и текст
+pub fn load_document(name: &str, content: String) -> Result<(String, String), String> {
+    if !name.to_lowercase().ends_with(".json") {
+        return Ok((name.to_string(), content));
+    }
+    match load_project(&content) {
+        Ok((ProjectFile::Full(scen), _)) => Ok((scen.file_name, scen.text)),
+        Ok((ProjectFile::Scens(_), _)) => Err(format!(
+            "В {} только список сценариев, нет текста документа",
+            name
+        )),
+        Err(e) => Err(format!("Не удалось загрузить {}: {}", name, e)),
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default, PartialEq)]
+pub enum MatchMode {
+    #[default]
+    First,
+    Last,
+    Nth(usize),
+    All,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default, PartialEq)]
+pub enum ValueType {
+    #[default]
+    String,
+    Integer,
+    Decimal,
+    Boolean,
+    Date,
+    DateTime,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Requirement {
+    Required,
+    #[default]
+    Optional,
+    InfoOnly,
+}
+
+impl Requirement {
+    pub fn severity(&self) -> ErrorEnum {
+        match self {
+            Requirement::Required => ErrorEnum::Error,
+            Requirement::Optional => ErrorEnum::Warning,
+            Requirement::InfoOnly => ErrorEnum::Info,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct FieldSpec {
+    pub name: String,
+    #[serde(default)]
+    pub value_type: ValueType,
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub requirement: Requirement,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ScenItem {
+    pub regex: String,
+    // запасные выражения: пробуются по порядку, если regex ничего не нашёл
+    pub alternatives: Vec<String>,
+    pub names: Vec<String>,
+    pub file_name_bool: bool,
+    pub table: bool,
+    pub position: usize,
+    pub table_mask: Option<String>,
+    pub mode: MatchMode,
+    pub transforms: Vec<Transform>,
+    pub fields: Vec<FieldSpec>,
+}
+
+impl ScenItem {
+    pub fn regexes(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.regex).chain(&self.alternatives)
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+// Multiple стоит первым: при чтении обратно (например, из воркера) массив иначе попал бы в Single
+pub enum OutputValue {
+    Multiple(Vec<Value>),
+    Single(Value),
+}
+
+// Положение совпадения: смещения в байтах, строка и столбец (в символах) с единицы.
+// Считается по тексту, в котором искал ScenItem (имя файла при file_name_bool)
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct OutputItem {
+    pub name: String,
+    pub value: OutputValue,
+    pub scen_index: usize,
+    // по одному на каждое значение; null там, где значение null
+    pub spans: Vec<Option<Span>>,
+    // какое выражение нашло значение: 0 — regex, 1 и далее — запасные
+    pub alternative: usize,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct OutputItemTabls {
+    pub names: Vec<String>,
+    // каждая строка таблицы: имя -> значение (null, если группа не найдена)
+    pub rows: Vec<BTreeMap<String, Option<Value>>>,
+    pub scen_index: usize,
+    // найденный блок таблицы
+    pub block: Span,
+    // положения значений, строка в строку с rows
+    pub spans: Vec<BTreeMap<String, Option<Span>>>,
+    // какое выражение разобрало строки: 0 — regex, 1 и далее — запасные
+    pub alternative: usize,
+}
+
+pub type ParseOutput = (Vec<OutputItem>, Vec<OutputItemTabls>, Vec<ErrorItem>);
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq)]
+pub enum ErrorCode {
+    InvalidRegex,
+    InvalidReplaceRegex,
+    InvalidTableMask,
+    MissingTableMask,
+    NoMatch,
+    MissingGroup,
+    TablePositionOutOfRange,
+    CoercionFailed,
+    // коды проверки сценариев (lint)
+    UnknownGroup,
+    EmptyName,
+    UnusedGroup,
+    UnknownField,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct ErrorItem {
+    pub message: String,
+    pub type_error: ErrorEnum,
+    pub code: ErrorCode,
+    // номер ScenItem в переданном списке
+    pub scen_index: usize,
+    pub field: Option<String>,
+    // текст ошибки regex или причина неудачного приведения типа
+    pub detail: Option<String>,
+}
+
+struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(text: &str) -> LineIndex {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex { line_starts }
+    }
+
+    fn span(&self, text: &str, start: usize, end: usize) -> Span {
+        let line = self.line_starts.partition_point(|&s| s <= start);
+        let line_start = self.line_starts[line - 1];
+        Span {
+            start,
+            end,
+            line,
+            column: text[line_start..start].chars().count() + 1,
+        }
+    }
+}
+
+fn requirement(r: &ScenItem, name: &str) -> Requirement {
+    r.fields
+        .iter()
+        .find(|f| f.name == name)
+        .map(|f| f.requirement)
+        .unwrap_or_default()
+}
+
+// Если не найдено всё выражение, важность определяется самым строгим из имён
+fn strictest(r: &ScenItem) -> ErrorEnum {
+    let requirements: Vec<Requirement> = r.names.iter().map(|n| requirement(r, n)).collect();
+    if requirements.contains(&Requirement::Required) {
+        ErrorEnum::Error
+    } else if requirements.is_empty() || requirements.contains(&Requirement::Optional) {
+        ErrorEnum::Warning
+    } else {
+        ErrorEnum::Info
+    }
+}
+
+fn no_match(r: &ScenItem, index: usize) -> ErrorItem {
+    let message = if r.alternatives.is_empty() {
+        format!("Не найдено совпадений по выражению: {}", &r.regex)
+    } else {
+        format!(
+            "Не найдено совпадений ни по одному из выражений: {}",
+            r.regexes().cloned().collect::<Vec<String>>().join(" | ")
+        )
+    };
+    ErrorItem {
+        message,
+        type_error: strictest(r),
+        code: ErrorCode::NoMatch,
+        scen_index: index,
+        field: None,
+        detail: None,
+    }
+}
+
+// Компилирует regex и запасные выражения; ошибочные пропускаются с ошибкой в errs
+fn compile_chain(
+    r: &ScenItem,
+    index: usize,
+    cache: &mut RegexCache,
+    errs: &mut Vec<ErrorItem>,
+) -> Vec<(usize, Regex)> {
+    let mut chain = vec![];
+    for (alternative, pattern) in r.regexes().enumerate() {
+        match cache.get(pattern) {
+            Ok(re) => chain.push((alternative, re)),
+            Err(e) => errs.push(ErrorItem {
+                message: format!("Есть ошибки в регулярном выражении: {}", pattern),
+                type_error: ErrorEnum::Error,
+                code: ErrorCode::InvalidRegex,
+                scen_index: index,
+                field: None,
+                detail: Some(e.to_string()),
+            }),
+        }
+    }
+    chain
+}
+
+fn typed_value(
+    value: &str,
+    index: usize,
+    name: &str,
+    spec: Option<&FieldSpec>,
+    out_error_vec: &mut Vec<ErrorItem>,
+) -> Option<Value> {
+    let Some(spec) = spec else {
+        return Some(Value::String(value.to_string()));
+    };
+    match coerce(value, &spec.value_type, spec.format.as_deref()) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            out_error_vec.push(ErrorItem {
+                message: format!(
+                    "Не удалось привести значение \"{}\" поля {} к типу {:?}: {}",
+                    value, name, spec.value_type, &e
+                ),
+                type_error: spec.requirement.severity(),
+                code: ErrorCode::CoercionFailed,
+                scen_index: index,
+                field: Some(name.to_string()),
+                detail: Some(e),
+            });
+            None
+        }
+    }
+}
+
+pub fn parse(file_name: String, text: String, scens: Vec<ScenItem>) -> ParseOutput {
+    parse_cached(file_name, text, scens, &mut RegexCache::new())
+}
+
+pub fn parse_cached(
+    file_name: String,
+    text: String,
+    scens: Vec<ScenItem>,
+    cache: &mut RegexCache,
+) -> ParseOutput {
+    let mut job = ParseJob::new(file_name, text, scens);
+    while !job.is_finished() {
+        job.step(cache);
+    }
+    job.finish()
+}
+
+// Разбор по одному сценарию за шаг, чтобы длинный разбор можно было прервать между шагами
+pub struct ParseJob {
+    file_name: String,
+    text: String,
+    scens: Vec<ScenItem>,
+    text_lines: LineIndex,
+    file_name_lines: LineIndex,
+    next: usize,
+    output: ParseOutput,
+}
+
+impl ParseJob {
+    pub fn new(file_name: String, text: String, scens: Vec<ScenItem>) -> ParseJob {
+        ParseJob {
+            text_lines: LineIndex::new(&text),
+            file_name_lines: LineIndex::new(&file_name),
+            file_name,
+            text,
+            scens,
+            next: 0,
+            output: (vec![], vec![], vec![]),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.scens.len()
+    }
+
+    pub fn done(&self) -> usize {
+        self.next
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.scens.len()
+    }
+
+    pub fn finish(self) -> ParseOutput {
+        self.output
+    }
+
+    pub fn step(&mut self, cache: &mut RegexCache) {
+        let index = self.next;
+        let Some(r) = self.scens.get(index) else {
+            return;
+        };
+        self.next += 1;
+        let (out_singl_vec, out_tabls_vec, out_error_vec) = &mut self.output;
+        let text: &str = &self.text;
+        let file_name: &str = &self.file_name;
+        let text_lines = &self.text_lines;
+        let file_name_lines = &self.file_name_lines;
+
+        let pipeline = match Pipeline::new(&r.transforms, cache) {
+            Ok(p) => p,
+            Err((pattern, e)) => {
+                out_error_vec.push(ErrorItem {
+                    message: format!("Есть ошибки в регулярном выражении замены: {}", pattern),
+                    type_error: ErrorEnum::Error,
+                    code: ErrorCode::InvalidReplaceRegex,
+                    scen_index: index,
+                    field: None,
+                    detail: Some(e.to_string()),
+                });
+                return;
+            }
+        };
+        if !r.table {
+            let chain = compile_chain(r, index, cache, out_error_vec);
+            if chain.is_empty() {
+                return;
+            }
+            let (local_text, lines) = if !r.file_name_bool {
+                (text, text_lines)
+            } else {
+                (file_name, file_name_lines)
+            };
+            // побеждает первое выражение, у которого есть совпадение
+            let found = chain.iter().find_map(|(alternative, re)| {
+                let caps_vec: Vec<Captures> = match r.mode {
+                    MatchMode::First => re.captures(local_text).into_iter().collect(),
+                    MatchMode::Last => re.captures_iter(local_text).last().into_iter().collect(),
+                    MatchMode::Nth(n) => re.captures_iter(local_text).nth(n).into_iter().collect(),
+                    MatchMode::All => re.captures_iter(local_text).collect(),
+                };
+                (!caps_vec.is_empty()).then_some((*alternative, caps_vec))
+            });
+            let Some((alternative, caps_vec)) = found else {
+                out_error_vec.push(no_match(r, index));
+                return;
+            };
+            for name in &r.names {
+                let spec = r.fields.iter().find(|f| &f.name == name);
+                // по элементу на каждое совпадение, чтобы в режиме All списки разных имён
+                // шли совпадение в совпадение
+                let raw_values = caps_vec
+                    .iter()
+                    .map(|caps| {
+                        caps.name(name.as_str()).map(|v| {
+                            (
+                                pipeline.apply(v.as_str()),
+                                lines.span(local_text, v.start(), v.end()),
+                            )
+                        })
+                    })
+                    .collect::<Vec<Option<(String, Span)>>>();
+                if raw_values.iter().all(|v| v.is_none()) {
+                    out_error_vec.push(ErrorItem {
+                        message: format!("Не найдено совпадений по имени: {}", &name),
+                        type_error: requirement(r, name).severity(),
+                        code: ErrorCode::MissingGroup,
+                        scen_index: index,
+                        field: Some(name.clone()),
+                        detail: None,
+                    });
+                    continue;
+                }
+                let missing: Vec<String> = raw_values
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, v)| v.is_none())
+                    .map(|(i, _)| (i + 1).to_string())
+                    .collect();
+                if !missing.is_empty() {
+                    out_error_vec.push(ErrorItem {
+                        message: format!("Имя найдено не во всех совпадениях: {}", &name),
+                        type_error: requirement(r, name).severity(),
+                        code: ErrorCode::MissingGroup,
+                        scen_index: index,
+                        field: Some(name.clone()),
+                        detail: Some(format!(
+                            "Номера совпадений без значения: {}",
+                            missing.join(", ")
+                        )),
+                    });
+                }
+                let mut values = vec![];
+                let mut spans = vec![];
+                for raw in raw_values {
+                    let typed = raw.and_then(|(v, span)| {
+                        typed_value(&v, index, name, spec, out_error_vec).map(|value| (value, span))
+                    });
+                    match typed {
+                        Some((value, span)) => {
+                            values.push(value);
+                            spans.push(Some(span));
+                        }
+                        None => {
+                            values.push(Value::Null);
+                            spans.push(None);
+                        }
+                    }
+                }
+                if spans.iter().all(|s| s.is_none()) {
+                    continue;
+                }
+                let value = if r.mode == MatchMode::All {
+                    OutputValue::Multiple(values)
+                } else {
+                    OutputValue::Single(values.remove(0))
+                };
+                out_singl_vec.push(OutputItem {
+                    name: name.clone(),
+                    value,
+                    scen_index: index,
+                    spans,
+                    alternative,
+                });
+            }
+        } else {
+            // если есть выражение для Таблицы
+            let Some(tm) = &r.table_mask else {
+                out_error_vec.push(ErrorItem {
+                    message: "Отсутствует регулярное вырожение для поиска таблицы".to_string(),
+                    type_error: ErrorEnum::Error,
+                    code: ErrorCode::MissingTableMask,
+                    scen_index: index,
+                    field: None,
+                    detail: None,
+                });
+                return;
+            };
+
+            let reg_table = match cache.get(tm) {
+                Ok(re) => re,
+                Err(e) => {
+                    out_error_vec.push(ErrorItem {
+                        message: format!("Есть ошибки в регулярном выражении: {}", tm),
+                        type_error: ErrorEnum::Error,
+                        code: ErrorCode::InvalidTableMask,
+                        scen_index: index,
+                        field: None,
+                        detail: Some(e.to_string()),
+                    });
+                    return;
+                }
+            };
+
+            let Some(table) = reg_table.captures_iter(text).nth(r.position) else {
+                out_error_vec.push(ErrorItem {
+                    message: format!(
+                        "Не найдено совпадений по выражению и позиции: {}, {}",
+                        tm, r.position
+                    ),
+                    type_error: strictest(r),
+                    code: ErrorCode::TablePositionOutOfRange,
+                    scen_index: index,
+                    field: None,
+                    detail: None,
+                });
+                return;
+            };
+
+            let Some(text_table) = table.get(0) else {
+                return;
+            };
+
+            let chain = compile_chain(r, index, cache, out_error_vec);
+            if chain.is_empty() {
+                return;
+            }
+            // побеждает первое выражение, нашедшее в таблице хотя бы одну строку
+            let Some((alternative, reg)) = chain
+                .iter()
+                .find(|(_, re)| re.is_match(text_table.as_str()))
+            else {
+                out_error_vec.push(no_match(r, index));
+                return;
+            };
+
+            let offset = text_table.start();
+            let mut rows: Vec<BTreeMap<String, Option<Value>>> = vec![];
+            let mut spans: Vec<BTreeMap<String, Option<Span>>> = vec![];
+            for item in reg.captures_iter(text_table.as_str()) {
+                let mut row: BTreeMap<String, Option<Value>> = BTreeMap::new();
+                let mut row_spans: BTreeMap<String, Option<Span>> = BTreeMap::new();
+                for name in &r.names {
+                    let spec = r.fields.iter().find(|f| &f.name == name);
+                    let span = item
+                        .name(name.as_str())
+                        .map(|v| text_lines.span(text, offset + v.start(), offset + v.end()));
+                    row_spans.insert(name.clone(), span);
+                    let value = match item.name(name.as_str()) {
+                        Some(v) => typed_value(
+                            &pipeline.apply(v.as_str()),
+                            index,
+                            name,
+                            spec,
+                            out_error_vec,
+                        ),
+                        None => {
+                            out_error_vec.push(ErrorItem {
+                                message: format!("Не найдено совпадений по имени: {}", &name),
+                                type_error: requirement(r, name).severity(),
+                                code: ErrorCode::MissingGroup,
+                                scen_index: index,
+                                field: Some(name.clone()),
+                                detail: None,
+                            });
+                            None
+                        }
+                    };
+                    row.insert(name.clone(), value);
+                }
+                rows.push(row);
+                spans.push(row_spans);
+            }
+            out_tabls_vec.push(OutputItemTabls {
+                names: r.names.clone(),
+                rows,
+                scen_index: index,
+                block: text_lines.span(text, text_table.start(), text_table.end()),
+                spans,
+                alternative: *alternative,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_bare_array_of_v1_items() {
+        let (project, report) =
+            load_project(r#"[{"regex": "(?<x>\\d)", "names": ["x", ""]}]"#).unwrap();
+        assert_eq!(report.from, 1);
+        let ProjectFile::Scens(scens) = project else {
+            panic!("ожидался список сценариев");
+        };
+        assert_eq!(scens[0].names, vec!["x".to_string()]);
+    }
+
+    #[test]
+    fn loads_v1_project() {
+        let (project, report) =
+            load_project(r#"{"file_name": "a", "text": "b", "scen": [{"regex": "x"}]}"#).unwrap();
+        assert_eq!(report.from, 1);
+        let ProjectFile::Full(scen) = project else {
+            panic!("ожидался проект");
+        };
+        assert_eq!(scen.version, FORMAT_VERSION);
+        assert_eq!(scen.file_name, "a");
+        assert_eq!(scen.scen.map(|s| s.len()), Some(1));
+    }
+
+    #[test]
+    fn rejects_version_zero_without_panic() {
+        assert!(load_project(r#"{"version": 0, "file_name": "a", "text": "b"}"#).is_err());
+    }
+
+    #[test]
+    fn rejects_future_version() {
+        let text = format!(r#"{{"version": {}, "scen": []}}"#, FORMAT_VERSION + 1);
+        assert!(load_project(&text).is_err());
+    }
+
+    fn table_item(regex: &str, alternatives: &[&str]) -> ScenItem {
+        ScenItem {
+            regex: regex.to_string(),
+            alternatives: alternatives.iter().map(|a| a.to_string()).collect(),
+            names: vec!["n".to_string()],
+            table: true,
+            table_mask: Some(r"(?s)BEGIN.*END".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn table_uses_first_matching_alternative() {
+        let r = table_item(r"x(?P<n>\d)", &[r"y(?P<n>\d)"]);
+        let (_, tables, errs) = parse("doc".to_string(), "BEGIN y1 y2 END".to_string(), vec![r]);
+        assert!(errs.is_empty());
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].alternative, 1);
+        assert_eq!(tables[0].rows.len(), 2);
+        assert_eq!(tables[0].rows[1]["n"], Some(json!("2")));
+    }
+
+    #[test]
+    fn table_without_matching_alternative_is_no_match() {
+        let r = table_item(r"x(?P<n>\d)", &[r"y(?P<n>\d)"]);
+        let (_, tables, errs) = parse("doc".to_string(), "BEGIN z1 END".to_string(), vec![r]);
+        assert!(tables.is_empty());
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].code, ErrorCode::NoMatch);
+        assert_eq!(errs[0].type_error, ErrorEnum::Warning);
+        assert!(errs[0].message.contains(r"x(?P<n>\d) | y(?P<n>\d)"));
+    }
+
+    #[test]
+    fn single_falls_back_to_alternative() {
+        let r = ScenItem {
+            regex: r"Итого: (?P<n>\d+)".to_string(),
+            alternatives: vec![r"Всего (?P<n>\d+)".to_string()],
+            names: vec!["n".to_string()],
+            ..Default::default()
+        };
+        let (items, _, errs) = parse("doc".to_string(), "Всего 42".to_string(), vec![r]);
+        assert!(errs.is_empty());
+        assert_eq!(items[0].alternative, 1);
+        assert_eq!(items[0].value, OutputValue::Single(json!("42")));
+    }
+
+    #[test]
+    fn all_mode_keeps_names_aligned() {
+        let r = ScenItem {
+            regex: r"(?P<a>a\d)?b(?P<c>\d)".to_string(),
+            names: vec!["a".to_string(), "c".to_string()],
+            mode: MatchMode::All,
+            ..Default::default()
+        };
+        let (items, _, errs) = parse("doc".to_string(), "b1 a2b3".to_string(), vec![r]);
+        assert_eq!(
+            items[0].value,
+            OutputValue::Multiple(vec![Value::Null, json!("a2")])
+        );
+        assert_eq!(items[0].spans[0], None);
+        assert!(items[0].spans[1].is_some());
+        assert_eq!(
+            items[1].value,
+            OutputValue::Multiple(vec![json!("1"), json!("3")])
+        );
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].code, ErrorCode::MissingGroup);
+        assert_eq!(errs[0].field.as_deref(), Some("a"));
+        assert_eq!(
+            errs[0].detail.as_deref(),
+            Some("Номера совпадений без значения: 1")
+        );
+    }
+
+    #[test]
+    fn match_modes_pick_matches() {
+        let item = |mode| ScenItem {
+            regex: r"(?P<n>\d)".to_string(),
+            names: vec!["n".to_string()],
+            mode,
+            ..Default::default()
+        };
+        let value = |mode| {
+            let (items, _, _) = parse("doc".to_string(), "1 2 3".to_string(), vec![item(mode)]);
+            items.into_iter().next().map(|i| i.value)
+        };
+        assert_eq!(
+            value(MatchMode::First),
+            Some(OutputValue::Single(json!("1")))
+        );
+        assert_eq!(
+            value(MatchMode::Last),
+            Some(OutputValue::Single(json!("3")))
+        );
+        assert_eq!(
+            value(MatchMode::Nth(1)),
+            Some(OutputValue::Single(json!("2")))
+        );
+        assert_eq!(value(MatchMode::Nth(5)), None);
+        assert_eq!(
+            value(MatchMode::All),
+            Some(OutputValue::Multiple(vec![
+                json!("1"),
+                json!("2"),
+                json!("3")
+            ]))
+        );
+    }
+
+    #[test]
+    fn span_counts_lines_and_chars() {
+        let r = ScenItem {
+            regex: r"ИНН: (?P<inn>\d+)".to_string(),
+            names: vec!["inn".to_string()],
+            ..Default::default()
+        };
+        let text = "первая\nИНН: 7701\n";
+        let (items, _, _) = parse("doc".to_string(), text.to_string(), vec![r]);
+        let span = items[0].spans[0].unwrap();
+        assert_eq!((span.start, span.end), (21, 25));
+        assert_eq!(&text[span.start..span.end], "7701");
+        assert_eq!((span.line, span.column), (2, 6));
+    }
+
+    #[test]
+    fn table_spans_are_shifted_by_block() {
+        let r = ScenItem {
+            regex: r"строка (?P<n>\d+)".to_string(),
+            names: vec!["n".to_string()],
+            table: true,
+            table_mask: Some(r"(?s)НАЧАЛО.*КОНЕЦ".to_string()),
+            ..Default::default()
+        };
+        let text = "шапка\nНАЧАЛО\nстрока 1\nстрока 22\nКОНЕЦ";
+        let (_, tables, _) = parse("doc".to_string(), text.to_string(), vec![r]);
+        let table = &tables[0];
+        assert_eq!((table.block.start, table.block.end), (11, text.len()));
+        assert_eq!((table.block.line, table.block.column), (2, 1));
+        let first = table.spans[0]["n"].unwrap();
+        assert_eq!(
+            (first.start, first.end, first.line, first.column),
+            (37, 38, 3, 8)
+        );
+        let second = table.spans[1]["n"].unwrap();
+        assert_eq!(
+            (second.start, second.end, second.line, second.column),
+            (52, 54, 4, 8)
+        );
+        assert_eq!(&text[second.start..second.end], "22");
+    }
+}
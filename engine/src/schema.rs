@@ -0,0 +1,19 @@
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+
+use crate::parse::{ParseOutput, Scen, ScenItem};
+
+// Схема файла проекта (Scen)
+pub fn project_schema() -> RootSchema {
+    schema_for!(Scen)
+}
+
+// Схема одного сценария; файл с голым массивом сценариев — массив таких объектов
+pub fn scen_item_schema() -> RootSchema {
+    schema_for!(ScenItem)
+}
+
+// Схема результата parse: [одиночные значения, таблицы, ошибки]
+pub fn output_schema() -> RootSchema {
+    schema_for!(ParseOutput)
+}
@@ -0,0 +1,175 @@
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::RegexCache;
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub enum Transform {
+    Trim,
+    CollapseWhitespace,
+    Replace {
+        pattern: String,
+        replacement: String,
+    },
+    Uppercase,
+    Lowercase,
+    StripThousands,
+    Substring {
+        start: usize,
+        length: Option<usize>,
+    },
+}
+
+enum Step {
+    Simple(Transform),
+    Replace(Regex, String),
+}
+
+// Скомпилированная цепочка преобразований одного ScenItem
+pub struct Pipeline {
+    steps: Vec<Step>,
+}
+
+impl Pipeline {
+    // При ошибке возвращает выражение замены, которое не удалось скомпилировать
+    pub fn new(
+        transforms: &[Transform],
+        cache: &mut RegexCache,
+    ) -> Result<Pipeline, (String, regex::Error)> {
+        let mut steps = vec![];
+        for t in transforms {
+            match t {
+                Transform::Replace {
+                    pattern,
+                    replacement,
+                } => {
+                    let re = cache.get(pattern).map_err(|e| (pattern.clone(), e))?;
+                    steps.push(Step::Replace(re, replacement.clone()));
+                }
+                other => steps.push(Step::Simple(other.clone())),
+            }
+        }
+        Ok(Pipeline { steps })
+    }
+
+    pub fn apply(&self, value: &str) -> String {
+        let mut out = value.to_string();
+        for step in &self.steps {
+            out = match step {
+                Step::Replace(re, replacement) => {
+                    re.replace_all(&out, replacement.as_str()).into_owned()
+                }
+                Step::Simple(t) => apply_simple(t, &out),
+            };
+        }
+        out
+    }
+}
+
+fn apply_simple(t: &Transform, value: &str) -> String {
+    match t {
+        Transform::Trim => value.trim().to_string(),
+        Transform::CollapseWhitespace => value.split_whitespace().collect::<Vec<&str>>().join(" "),
+        Transform::Uppercase => value.to_uppercase(),
+        Transform::Lowercase => value.to_lowercase(),
+        Transform::StripThousands => strip_thousands(value),
+        Transform::Substring { start, length } => {
+            let chars = value.chars().skip(*start);
+            match length {
+                Some(l) => chars.take(*l).collect(),
+                None => chars.collect(),
+            }
+        }
+        Transform::Replace { .. } => value.to_string(),
+    }
+}
+
+// Убирает разделители разрядов ("1 234 567", "1'234") только между цифрами
+fn strip_thousands(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut out = String::with_capacity(value.len());
+    for (i, c) in chars.iter().enumerate() {
+        let separator = matches!(c, ' ' | '\u{a0}' | '\u{202f}' | '\'');
+        if separator
+            && i > 0
+            && chars[i - 1].is_ascii_digit()
+            && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())
+        {
+            continue;
+        }
+        out.push(*c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(transforms: &[Transform], value: &str) -> String {
+        Pipeline::new(transforms, &mut RegexCache::new())
+            .unwrap()
+            .apply(value)
+    }
+
+    #[test]
+    fn strips_thousands_only_between_digits() {
+        let t = [Transform::StripThousands];
+        assert_eq!(apply(&t, "1 234 567"), "1234567");
+        assert_eq!(apply(&t, "1\u{a0}234\u{202f}567"), "1234567");
+        assert_eq!(apply(&t, "1'234"), "1234");
+        assert_eq!(apply(&t, "итого 1 234 руб"), "итого 1234 руб");
+        assert_eq!(apply(&t, "12 а 3"), "12 а 3");
+    }
+
+    #[test]
+    fn substring_counts_chars() {
+        let t = [Transform::Substring {
+            start: 1,
+            length: Some(3),
+        }];
+        assert_eq!(apply(&t, "№ПРИВЕТ"), "ПРИ");
+        let t = [Transform::Substring {
+            start: 4,
+            length: None,
+        }];
+        assert_eq!(apply(&t, "ИНН:7701"), "7701");
+        assert_eq!(apply(&t, "ИН"), "");
+    }
+
+    #[test]
+    fn steps_run_in_order() {
+        let t = [
+            Transform::Trim,
+            Transform::Replace {
+                pattern: r"\s+".to_string(),
+                replacement: "_".to_string(),
+            },
+            Transform::Uppercase,
+        ];
+        assert_eq!(apply(&t, "  ооо  ромашка "), "ООО_РОМАШКА");
+        let t = [
+            Transform::Uppercase,
+            Transform::Replace {
+                pattern: "ооо".to_string(),
+                replacement: "".to_string(),
+            },
+        ];
+        assert_eq!(apply(&t, "ооо ромашка"), "ООО РОМАШКА");
+        let t = [Transform::CollapseWhitespace, Transform::Lowercase];
+        assert_eq!(apply(&t, " А \n  Б "), "а б");
+    }
+
+    #[test]
+    fn invalid_replace_pattern_is_reported() {
+        let t = [Transform::Replace {
+            pattern: "(".to_string(),
+            replacement: String::new(),
+        }];
+        let Err((pattern, _)) = Pipeline::new(&t, &mut RegexCache::new()) else {
+            panic!("ожидалась ошибка выражения");
+        };
+        assert_eq!(pattern, "(");
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use clap::Parser;
+use json_editor_engine::{load_project, parse_cached, RegexCache, ScenItem};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Parser)]
+#[command(
+    version,
+    about = "HTTP-сервис разбора документов сценариями из каталога (только localhost)"
+)]
+struct Args {
+    #[arg(
+        short,
+        long,
+        default_value = "scenarios",
+        help = "Каталог с наборами сценариев: каждый *.json — отдельный набор с именем файла"
+    )]
+    dir: PathBuf,
+    #[arg(short, long, default_value_t = 8080, help = "Порт")]
+    port: u16,
+    #[arg(short, long, default_value_t = 4, help = "Число потоков обработки запросов")]
+    threads: usize,
+}
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct State {
+    sets: HashMap<String, Vec<ScenItem>>,
+    // набор -> ошибка последней загрузки его файла
+    errors: BTreeMap<String, String>,
+}
+
+type Sets = Arc<RwLock<State>>;
+
+#[derive(Deserialize)]
+struct Document {
+    #[serde(default)]
+    file_name: String,
+    text: String,
+}
+
+fn load_scens(text: &str) -> Result<Vec<ScenItem>, String> {
+    load_project(text)
+        .map(|(project, _)| project.into_scens())
+        .map_err(|e| e.to_string())
+}
+
+fn set_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut files: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .filter_map(|p| Some((p.file_stem()?.to_string_lossy().to_string(), p)))
+        .collect();
+    files.sort();
+    files
+}
+
+// Файлы с ошибками не выключают наборы: остаётся ранее загруженная версия из previous,
+// а ошибка попадает в errors
+fn load_sets(dir: &Path, previous: &HashMap<String, Vec<ScenItem>>) -> State {
+    let mut state = State::default();
+    for (name, path) in set_files(dir) {
+        let loaded = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| load_scens(&text));
+        match loaded {
+            Ok(scens) => {
+                state.sets.insert(name, scens);
+            }
+            Err(e) => {
+                match previous.get(&name) {
+                    Some(scens) => {
+                        eprintln!(
+                            "Набор {} не загружен, оставлена прежняя версия: {}",
+                            path.display(),
+                            e
+                        );
+                        state.sets.insert(name.clone(), scens.clone());
+                    }
+                    None => eprintln!("Набор {} не загружен: {}", path.display(), e),
+                }
+                state.errors.insert(name, e);
+            }
+        }
+    }
+    state
+}
+
+fn snapshot(dir: &Path) -> Vec<(PathBuf, Option<SystemTime>, u64)> {
+    set_files(dir)
+        .into_iter()
+        .map(|(_, path)| {
+            let meta = fs::metadata(&path).ok();
+            let modified = meta.as_ref().and_then(|m| m.modified().ok());
+            let len = meta.map(|m| m.len()).unwrap_or(0);
+            (path, modified, len)
+        })
+        .collect()
+}
+
+// Каталог опрашивается раз в POLL_INTERVAL; при любом изменении наборы перечитываются целиком
+fn watch(dir: PathBuf, sets: Sets) {
+    thread::spawn(move || {
+        let mut last = snapshot(&dir);
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = snapshot(&dir);
+            if current == last {
+                continue;
+            }
+            last = current;
+            let previous = match sets.read() {
+                Ok(state) => state.sets.clone(),
+                Err(_) => continue,
+            };
+            let loaded = load_sets(&dir, &previous);
+            eprintln!("Наборы сценариев перечитаны: {}", loaded.sets.len());
+            if let Ok(mut sets) = sets.write() {
+                *sets = loaded;
+            }
+        }
+    });
+}
+
+// Имя набора становится именем файла, поэтому допускаются только безопасные символы
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn json_response(status: u16, body: &Value) -> Response<std::io::Cursor<Vec<u8>>> {
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json; charset=utf-8"[..])
+        .expect("Некорректный заголовок");
+    Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header)
+}
+
+fn error(status: u16, message: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    json_response(status, &json!({ "error": message }))
+}
+
+fn handle(
+    request: &mut Request,
+    dir: &Path,
+    sets: &Sets,
+    cache: &mut RegexCache,
+) -> Response<std::io::Cursor<Vec<u8>>> {
+    let url = request.url().split('?').next().unwrap_or_default().to_string();
+    let parts: Vec<&str> = url.trim_matches('/').split('/').collect();
+    let mut body = String::new();
+    if let Err(e) = request.as_reader().read_to_string(&mut body) {
+        return error(400, &format!("Не удалось прочитать тело запроса: {}", e));
+    }
+    match (request.method(), parts.as_slice()) {
+        (Method::Get, ["health"]) => {
+            let Ok(state) = sets.read() else {
+                return error(500, "Состояние наборов недоступно");
+            };
+            json_response(
+                200,
+                &json!({ "status": "ok", "sets": state.sets.len(), "errors": state.errors }),
+            )
+        }
+        (Method::Get, ["scenarios"]) => {
+            let mut names: Vec<String> = sets
+                .read()
+                .map(|s| s.sets.keys().cloned().collect())
+                .unwrap_or_default();
+            names.sort();
+            json_response(200, &json!(names))
+        }
+        (Method::Put, ["scenarios", name]) => {
+            if !valid_name(name) {
+                return error(400, "Недопустимое имя набора");
+            }
+            let scens = match load_scens(&body) {
+                Ok(scens) => scens,
+                Err(e) => return error(400, &format!("Набор сценариев не загружен: {}", e)),
+            };
+            let path = dir.join(format!("{}.json", name));
+            if let Err(e) = fs::create_dir_all(dir).and_then(|_| fs::write(&path, &body)) {
+                return error(500, &format!("Не удалось записать {}: {}", path.display(), e));
+            }
+            let count = scens.len();
+            if let Ok(mut sets) = sets.write() {
+                sets.sets.insert(name.to_string(), scens);
+                sets.errors.remove(*name);
+            }
+            json_response(201, &json!({ "name": name, "scenarios": count }))
+        }
+        (Method::Post, ["parse", name]) => {
+            let Some(scens) = sets.read().ok().and_then(|s| s.sets.get(*name).cloned()) else {
+                return error(404, &format!("Нет набора сценариев {}", name));
+            };
+            let document: Document = match serde_json::from_str(&body) {
+                Ok(d) => d,
+                Err(e) => return error(400, &format!("Ожидается {{file_name, text}}: {}", e)),
+            };
+            let output = parse_cached(document.file_name, document.text, scens, cache);
+            json_response(200, &json!(output))
+        }
+        _ => error(404, "Нет такого адреса"),
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let sets: Sets = Arc::new(RwLock::new(load_sets(&args.dir, &HashMap::new())));
+    watch(args.dir.clone(), sets.clone());
+
+    let address = format!("127.0.0.1:{}", args.port);
+    let server = match Server::http(&address) {
+        Ok(server) => Arc::new(server),
+        Err(e) => {
+            eprintln!("Не удалось запустить сервер на {}: {}", address, e);
+            std::process::exit(2);
+        }
+    };
+    eprintln!("Сервер слушает http://{}", address);
+
+    // у каждого потока свой кэш выражений, наборы общие
+    let workers: Vec<thread::JoinHandle<()>> = (0..args.threads.max(1))
+        .map(|_| {
+            let server = server.clone();
+            let sets = sets.clone();
+            let dir = args.dir.clone();
+            thread::spawn(move || {
+                let mut cache = RegexCache::new();
+                for mut request in server.incoming_requests() {
+                    let response = handle(&mut request, &dir, &sets, &mut cache);
+                    if let Err(e) = request.respond(response) {
+                        eprintln!("Не удалось отправить ответ: {}", e);
+                    }
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+}
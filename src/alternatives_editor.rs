@@ -0,0 +1,64 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct AlternativesEditorProps {
+    pub alternatives: Vec<String>,
+    pub on_change: Callback<Vec<String>>,
+}
+
+#[function_component]
+pub fn AlternativesEditor(props: &AlternativesEditorProps) -> Html {
+    let on_add = {
+        let alternatives = props.alternatives.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |_| {
+            let mut l_alternatives = alternatives.clone();
+            l_alternatives.push(String::new());
+            on_change.emit(l_alternatives);
+        })
+    };
+
+    let edit = |index: usize| {
+        let alternatives = props.alternatives.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let mut l_alternatives = alternatives.clone();
+                l_alternatives[index] = input.value();
+                on_change.emit(l_alternatives);
+            }
+        })
+    };
+
+    let remove = |index: usize| {
+        let alternatives = props.alternatives.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut l_alternatives = alternatives.clone();
+            l_alternatives.remove(index);
+            on_change.emit(l_alternatives);
+        })
+    };
+
+    html! {
+        <div class="transforms">
+            {
+                props.alternatives.iter().enumerate().map(|(index, pattern)| {
+                    html! {
+                        <div class="transform-item">
+                            <span>{format!("{}.", index + 1)}</span>
+                            <input type="text" value={pattern.clone()} oninput={edit(index)}/>
+                            <span class="entry-buttons">
+                                <button class="delete-btn" onclick={remove(index)}>{"Удалить"}</button>
+                            </span>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+            <div class="transform-add">
+                <button class="test-btn" onclick={on_add}>{"Добавить"}</button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,261 @@
+use std::cell::RefCell;
+use std::io::{Cursor, Read};
+use std::rc::Rc;
+
+use gloo_file::callbacks::{read_as_bytes, read_as_text};
+use gloo_file::File;
+use json_editor_engine::{load_document, ErrorEnum, ParseOutput};
+use yew::prelude::*;
+use zip::ZipArchive;
+
+use crate::export::{ExportButtons, ExportDocument};
+use crate::results::ResultsPanel;
+
+// Имя в списке (для архива — «архив/путь») и имя файла с текстом или причина ошибки
+pub type LoadedDocument = (String, Result<(String, String), String>);
+
+#[derive(Clone, PartialEq)]
+pub enum DocumentState {
+    Pending,
+    Done(ParseOutput),
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct BatchDocument {
+    pub name: String,
+    pub state: DocumentState,
+}
+
+#[derive(Default, PartialEq)]
+pub struct Batch {
+    pub documents: Vec<BatchDocument>,
+}
+
+pub enum BatchAction {
+    Start(Vec<BatchDocument>),
+    Done(usize, ParseOutput),
+    Cancel,
+    Clear,
+}
+
+impl Reducible for Batch {
+    type Action = BatchAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut documents = self.documents.clone();
+        match action {
+            BatchAction::Start(l_documents) => documents = l_documents,
+            BatchAction::Done(index, output) => {
+                if let Some(document) = documents.get_mut(index) {
+                    document.state = DocumentState::Done(output);
+                }
+            }
+            BatchAction::Cancel => {
+                for document in documents.iter_mut() {
+                    if document.state == DocumentState::Pending {
+                        document.state = DocumentState::Cancelled;
+                    }
+                }
+            }
+            BatchAction::Clear => documents.clear(),
+        }
+        Rc::new(Batch { documents })
+    }
+}
+
+fn base_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn unzip(name: &str, bytes: &[u8]) -> Vec<LoadedDocument> {
+    let mut archive = match ZipArchive::new(Cursor::new(bytes)) {
+        Ok(archive) => archive,
+        Err(e) => {
+            return vec![(
+                name.to_string(),
+                Err(format!("Не удалось открыть архив: {}", e)),
+            )]
+        }
+    };
+    let mut documents = vec![];
+    for i in 0..archive.len() {
+        let mut entry = match archive.by_index(i) {
+            Ok(entry) => entry,
+            Err(e) => {
+                documents.push((
+                    format!("{}/#{}", name, i),
+                    Err(format!("Не удалось прочитать запись архива: {}", e)),
+                ));
+                continue;
+            }
+        };
+        // служебные записи архиваторов macOS
+        if entry.is_dir() || entry.name().starts_with("__MACOSX/") {
+            continue;
+        }
+        let entry_name = format!("{}/{}", name, entry.name());
+        let mut content = String::new();
+        let document = match entry.read_to_string(&mut content) {
+            Ok(_) => load_document(base_name(entry.name()), content),
+            Err(e) => Err(format!("Не удалось прочитать как текст: {}", e)),
+        };
+        documents.push((entry_name, document));
+    }
+    documents
+}
+
+// Читает все файлы (ZIP-архивы распаковываются) и отдаёт документы в исходном порядке
+pub fn read_files(files: Vec<File>, on_read: Callback<Vec<LoadedDocument>>) {
+    let loaded = Rc::new(RefCell::new(vec![None; files.len()]));
+    for (index, file) in files.into_iter().enumerate() {
+        let name = file.name();
+        let finish = {
+            let loaded = loaded.clone();
+            let on_read = on_read.clone();
+            move |documents: Vec<LoadedDocument>| {
+                let mut l_loaded = loaded.borrow_mut();
+                l_loaded[index] = Some(documents);
+                if l_loaded.iter().all(Option::is_some) {
+                    on_read.emit(l_loaded.drain(..).flatten().flatten().collect());
+                }
+            }
+        };
+        if name.to_lowercase().ends_with(".zip") {
+            let reader = read_as_bytes(&file, move |result| {
+                finish(match result {
+                    Ok(bytes) => unzip(&name, &bytes),
+                    Err(e) => vec![(name, Err(format!("Не удалось прочитать файл: {}", e)))],
+                })
+            });
+            std::mem::forget(reader);
+        } else {
+            let reader = read_as_text(&file, move |result| {
+                let document = match result {
+                    Ok(text) => load_document(&name, text),
+                    Err(e) => Err(format!("Не удалось прочитать файл как текст: {}", e)),
+                };
+                finish(vec![(name, document)])
+            });
+            std::mem::forget(reader);
+        }
+    }
+}
+
+fn count(output: &ParseOutput, severity: ErrorEnum) -> usize {
+    output.2.iter().filter(|e| e.type_error == severity).count()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct BatchPanelProps {
+    pub documents: Vec<BatchDocument>,
+    pub on_cancel: Callback<MouseEvent>,
+    pub on_close: Callback<MouseEvent>,
+}
+
+#[function_component]
+pub fn BatchPanel(props: &BatchPanelProps) -> Html {
+    let selected = use_state(|| None::<usize>);
+
+    let total = props.documents.len();
+    let pending = props
+        .documents
+        .iter()
+        .filter(|d| d.state == DocumentState::Pending)
+        .count();
+
+    let on_select = |index: usize| {
+        let selected = selected.clone();
+        Callback::from(move |_| {
+            selected.set(if *selected == Some(index) {
+                None
+            } else {
+                Some(index)
+            });
+        })
+    };
+
+    let on_close_document = {
+        let selected = selected.clone();
+        Callback::from(move |_| {
+            selected.set(None);
+        })
+    };
+
+    let selected_document = selected
+        .and_then(|index| props.documents.get(index))
+        .and_then(|d| match &d.state {
+            DocumentState::Done(output) => Some((d.name.clone(), output.clone())),
+            _ => None,
+        });
+
+    let exported: Vec<ExportDocument> = props
+        .documents
+        .iter()
+        .filter_map(|d| match &d.state {
+            DocumentState::Done(output) => Some((Some(d.name.clone()), output.clone())),
+            _ => None,
+        })
+        .collect();
+
+    html! {
+        <div class="results batch">
+            <div class="results-header">
+                <h4>{format!("Пакетная обработка: документов {}", total)}</h4>
+                <span class="entry-buttons">
+                    if pending == 0 && !exported.is_empty() {
+                        <ExportButtons documents={exported} name="batch"/>
+                    }
+                    <button class="delete-btn" onclick={props.on_close.clone()}>{"Закрыть"}</button>
+                </span>
+            </div>
+            if pending > 0 {
+                <div class="progress">
+                    <progress value={format!("{}", total - pending)} max={format!("{}", total)}/>
+                    <span>{format!("Обработано документов: {} из {}", total - pending, total)}</span>
+                    <button class="delete-btn" onclick={props.on_cancel.clone()}>{"Отменить"}</button>
+                </div>
+            }
+            <table>
+                <tr>
+                    <th>{"Документ"}</th>
+                    <th>{"Значений"}</th>
+                    <th>{"Предупреждений"}</th>
+                    <th>{"Ошибок"}</th>
+                </tr>
+                {
+                    props.documents.iter().enumerate().map(|(index, document)| {
+                        let cells = match &document.state {
+                            DocumentState::Done(output) => {
+                                let values = output.0.len() + output.1.iter().map(|t| t.rows.len()).sum::<usize>();
+                                html! {
+                                    <>
+                                        <td>{values}</td>
+                                        <td>{count(output, ErrorEnum::Warning)}</td>
+                                        <td>{count(output, ErrorEnum::Error)}</td>
+                                    </>
+                                }
+                            }
+                            DocumentState::Pending => html! { <td colspan="3">{"Обрабатывается…"}</td> },
+                            DocumentState::Cancelled => html! { <td colspan="3">{"Отменено"}</td> },
+                            DocumentState::Failed(message) => html! { <td colspan="3" class="batch-failed">{message.clone()}</td> },
+                        };
+                        let done = matches!(document.state, DocumentState::Done(_));
+                        html! {
+                            <tr
+                                class={classes!(done.then_some("batch-row"), (*selected == Some(index)).then_some("batch-selected"))}
+                                onclick={done.then(|| on_select(index))}>
+                                <td>{document.name.clone()}</td>
+                                {cells}
+                            </tr>
+                        }
+                    }).collect::<Html>()
+                }
+            </table>
+            if let Some((name, output)) = selected_document {
+                <ResultsPanel output={output} name={name} on_close={on_close_document}/>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,6 @@
+use gloo_worker::Registrable;
+use json_editor::worker::{JsonCodec, ParseWorker};
+
+fn main() {
+    ParseWorker::registrar().encoding::<JsonCodec>().register();
+}
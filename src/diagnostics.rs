@@ -0,0 +1,130 @@
+use serde_json::error::Category;
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+#[derive(Clone, PartialEq)]
+pub struct LoadError {
+    pub file: String,
+    pub message: String,
+    // строка и столбец с единицы, 0 — если позиция неизвестна
+    pub line: usize,
+    pub column: usize,
+}
+
+impl LoadError {
+    pub fn from_serde(file: &str, e: &serde_json::Error) -> LoadError {
+        let kind = match e.classify() {
+            Category::Syntax => "Синтаксическая ошибка JSON",
+            Category::Data => "JSON не соответствует формату проекта",
+            Category::Eof => "Файл обрывается раньше времени",
+            Category::Io => "Ошибка чтения",
+        };
+        LoadError {
+            file: file.to_string(),
+            message: format!("{}: {}", kind, e),
+            line: e.line(),
+            column: e.column(),
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct LoadDiagnosticsProps {
+    pub error: LoadError,
+    pub content: Option<String>,
+    pub on_apply: Callback<String>,
+    pub on_close: Callback<MouseEvent>,
+}
+
+const PREVIEW_LINES: usize = 2;
+
+fn preview(content: &str, line: usize, column: usize) -> Html {
+    if line == 0 {
+        return html! {};
+    }
+    let from = line.saturating_sub(PREVIEW_LINES).max(1);
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l))
+        .skip(from - 1)
+        .take(line - from + PREVIEW_LINES + 1)
+        .map(|(number, l)| {
+            if number == line {
+                html! {
+                    <>
+                        <div class="preview-line preview-error">{format!("{:>5} | {}", number, l)}</div>
+                        <div class="preview-line preview-error">{format!("{:>5} | {}^", "", " ".repeat(column.saturating_sub(1)))}</div>
+                    </>
+                }
+            } else {
+                html! { <div class="preview-line">{format!("{:>5} | {}", number, l)}</div> }
+            }
+        })
+        .collect::<Html>()
+}
+
+#[function_component]
+pub fn LoadDiagnostics(props: &LoadDiagnosticsProps) -> Html {
+    let editing = use_state(|| false);
+    let edited = use_state(|| props.content.clone().unwrap_or_default());
+
+    {
+        let edited = edited.clone();
+        use_effect_with(props.content.clone(), move |content| {
+            edited.set(content.clone().unwrap_or_default());
+        });
+    }
+
+    let on_edit = {
+        let editing = editing.clone();
+        Callback::from(move |_| {
+            editing.set(!*editing);
+        })
+    };
+
+    let on_input = {
+        let edited = edited.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlTextAreaElement>() {
+                edited.set(input.value());
+            }
+        })
+    };
+
+    let on_apply = {
+        let edited = edited.clone();
+        let on_apply = props.on_apply.clone();
+        Callback::from(move |_| {
+            on_apply.emit((*edited).clone());
+        })
+    };
+
+    let error = &props.error;
+    html! {
+        <div class="diagnostics">
+            <div class="results-header">
+                <h4>{format!("Не удалось загрузить {}", error.file)}</h4>
+                <span class="entry-buttons">
+                    if props.content.is_some() {
+                        <button class="edit-btn" onclick={on_edit}>{"Исправить вручную"}</button>
+                    }
+                    <button class="delete-btn" onclick={props.on_close.clone()}>{"Закрыть"}</button>
+                </span>
+            </div>
+            <p>{error.message.clone()}</p>
+            if error.line > 0 {
+                <p>{format!("Строка {}, столбец {}", error.line, error.column)}</p>
+            }
+            if let Some(content) = &props.content {
+                <pre class="preview">{preview(content, error.line, error.column)}</pre>
+            }
+            if *editing {
+                <textarea class="raw-editor" value={(*edited).clone()} oninput={on_input}/>
+                <div class="buttons">
+                    <button class="save-btn" onclick={on_apply}>{"Применить"}</button>
+                </div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+
+use gloo_utils::window;
+use json_editor_engine::{OutputValue, ParseOutput};
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use serde_json::Value;
+use yew::prelude::*;
+
+use crate::download;
+
+const XLSX_MIME: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+// Имя исходного файла (только в пакетной обработке) и результат разбора
+pub type ExportDocument = (Option<String>, ParseOutput);
+
+#[derive(PartialEq)]
+pub struct Sheet {
+    pub name: String,
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+// Первый лист — сводка одиночных полей, дальше по листу на каждую таблицу.
+// Таблицы одного сценария из разных документов сливаются в один лист
+pub fn sheets(documents: &[ExportDocument]) -> Vec<Sheet> {
+    let with_source = documents.iter().any(|(source, _)| source.is_some());
+    let source_header = |header: Vec<String>| {
+        if with_source {
+            std::iter::once("Файл".to_string()).chain(header).collect()
+        } else {
+            header
+        }
+    };
+    let source_row = |source: &Option<String>, row: Vec<Value>| {
+        if with_source {
+            std::iter::once(Value::String(source.clone().unwrap_or_default()))
+                .chain(row)
+                .collect()
+        } else {
+            row
+        }
+    };
+
+    let mut summary = Sheet {
+        name: "Сводка".to_string(),
+        header: source_header(vec!["Поле".to_string(), "Значение".to_string()]),
+        rows: vec![],
+    };
+    let mut tables: BTreeMap<usize, Sheet> = BTreeMap::new();
+    for (source, (singles, l_tables, _)) in documents {
+        for item in singles {
+            let values = match &item.value {
+                OutputValue::Single(v) => vec![v.clone()],
+                OutputValue::Multiple(vs) => vs.clone(),
+            };
+            for value in values {
+                summary.rows.push(source_row(
+                    source,
+                    vec![Value::String(item.name.clone()), value],
+                ));
+            }
+        }
+        for table in l_tables {
+            let sheet = tables.entry(table.scen_index).or_insert_with(|| Sheet {
+                name: String::new(),
+                header: source_header(table.names.clone()),
+                rows: vec![],
+            });
+            for row in &table.rows {
+                let cells = table
+                    .names
+                    .iter()
+                    .map(|name| row.get(name).cloned().flatten().unwrap_or(Value::Null))
+                    .collect();
+                sheet.rows.push(source_row(source, cells));
+            }
+        }
+    }
+    let mut out = vec![summary];
+    for (position, mut sheet) in tables.into_values().enumerate() {
+        sheet.name = format!("Таблица {}", position + 1);
+        out.push(sheet);
+    }
+    out
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+// Десятичная запятая, иначе русский Excel примет 1234.56 за текст или дату
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Number(n) => n.to_string().replace('.', ","),
+        other => cell_text(other),
+    }
+}
+
+// Разделитель «;» и BOM — чтобы русский Excel открывал файл без мастера импорта
+pub fn to_csv(sheet: &Sheet) -> String {
+    let line = |cells: Vec<String>| {
+        cells
+            .iter()
+            .map(|c| {
+                if c.contains([';', '"', '\n', '\r']) {
+                    format!("\"{}\"", c.replace('"', "\"\""))
+                } else {
+                    c.clone()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(";")
+    };
+    let mut out = String::from("\u{feff}");
+    out.push_str(&line(sheet.header.clone()));
+    out.push_str("\r\n");
+    for row in &sheet.rows {
+        out.push_str(&line(row.iter().map(csv_cell).collect()));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+pub fn to_xlsx(sheets: &[Sheet]) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    for sheet in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&sheet.name)?;
+        for (col, title) in sheet.header.iter().enumerate() {
+            worksheet.write_string_with_format(0, col as u16, title, &bold)?;
+        }
+        worksheet.set_freeze_panes(1, 0)?;
+        for (row, cells) in sheet.rows.iter().enumerate() {
+            let row = row as u32 + 1;
+            for (col, value) in cells.iter().enumerate() {
+                let col = col as u16;
+                match value {
+                    Value::Null => {}
+                    Value::Bool(b) => {
+                        worksheet.write_boolean(row, col, *b)?;
+                    }
+                    Value::Number(n) => match n.as_f64() {
+                        Some(f) => {
+                            worksheet.write_number(row, col, f)?;
+                        }
+                        None => {
+                            worksheet.write_string(row, col, n.to_string())?;
+                        }
+                    },
+                    other => {
+                        worksheet.write_string(row, col, cell_text(other))?;
+                    }
+                }
+            }
+        }
+    }
+    workbook.save_to_buffer()
+}
+
+fn base_name(name: &str) -> String {
+    let name = name.rsplit('/').next().unwrap_or(name);
+    let base = match name.rsplit_once('.') {
+        Some((base, _)) if !base.is_empty() => base,
+        _ => name,
+    };
+    if base.trim().is_empty() {
+        "results".to_string()
+    } else {
+        base.to_string()
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ExportButtonsProps {
+    pub documents: Vec<ExportDocument>,
+    // имя документа или пакета, из него строятся имена файлов
+    #[prop_or_default]
+    pub name: AttrValue,
+}
+
+#[function_component]
+pub fn ExportButtons(props: &ExportButtonsProps) -> Html {
+    let sheets = use_memo(props.documents.clone(), |documents| sheets(documents));
+    let base = base_name(&props.name);
+
+    let on_xlsx = {
+        let sheets = sheets.clone();
+        let base = base.clone();
+        Callback::from(move |_| match to_xlsx(&sheets) {
+            Ok(data) => download(&format!("{}.xlsx", base), data.as_slice(), XLSX_MIME),
+            Err(e) => {
+                let _ = window().alert_with_message(&format!("Не удалось создать XLSX: {}", e));
+            }
+        })
+    };
+
+    let on_csv = |index: usize| {
+        let sheets = sheets.clone();
+        let base = base.clone();
+        Callback::from(move |_| {
+            if let Some(sheet) = sheets.get(index) {
+                download(
+                    &format!("{} - {}.csv", base, sheet.name),
+                    to_csv(sheet).as_str(),
+                    "text/csv",
+                );
+            }
+        })
+    };
+
+    html! {
+        <span class="export-buttons">
+            <button class="edit-btn" onclick={on_xlsx}>{"XLSX"}</button>
+            {
+                sheets.iter().enumerate().map(|(index, sheet)| html! {
+                    <button class="edit-btn" onclick={on_csv(index)}>{format!("CSV: {}", sheet.name)}</button>
+                }).collect::<Html>()
+            }
+        </span>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use json_editor_engine::parse::Span;
+    use json_editor_engine::{OutputItem, OutputItemTabls};
+    use serde_json::json;
+
+    use super::*;
+
+    fn single(name: &str, value: OutputValue) -> OutputItem {
+        OutputItem {
+            name: name.to_string(),
+            value,
+            scen_index: 0,
+            spans: vec![],
+            alternative: 0,
+        }
+    }
+
+    fn table(scen_index: usize, rows: &[(&str, Value)]) -> OutputItemTabls {
+        let span = Span {
+            start: 0,
+            end: 0,
+            line: 1,
+            column: 1,
+        };
+        OutputItemTabls {
+            names: vec!["n".to_string()],
+            rows: rows
+                .iter()
+                .map(|(name, value)| BTreeMap::from([(name.to_string(), Some(value.clone()))]))
+                .collect(),
+            scen_index,
+            block: span,
+            spans: vec![],
+            alternative: 0,
+        }
+    }
+
+    #[test]
+    fn single_document_has_no_source_column() {
+        let documents = vec![(
+            None,
+            (
+                vec![single(
+                    "sum",
+                    OutputValue::Multiple(vec![json!(1), json!(2)]),
+                )],
+                vec![],
+                vec![],
+            ),
+        )];
+        let sheets = sheets(&documents);
+        assert_eq!(sheets.len(), 1);
+        assert_eq!(sheets[0].header, vec!["Поле", "Значение"]);
+        // каждое значение Multiple — отдельная строка
+        assert_eq!(
+            sheets[0].rows,
+            vec![vec![json!("sum"), json!(1)], vec![json!("sum"), json!(2)]]
+        );
+    }
+
+    #[test]
+    fn batch_merges_tables_by_scenario_with_source() {
+        let documents = vec![
+            (
+                Some("a.txt".to_string()),
+                (
+                    vec![single("inn", OutputValue::Single(json!("7701")))],
+                    vec![
+                        table(2, &[("n", json!("1"))]),
+                        table(5, &[("n", json!("x"))]),
+                    ],
+                    vec![],
+                ),
+            ),
+            (
+                Some("b.txt".to_string()),
+                (vec![], vec![table(2, &[("n", json!("2"))])], vec![]),
+            ),
+        ];
+        let sheets = sheets(&documents);
+        assert_eq!(sheets.len(), 3);
+        assert_eq!(sheets[0].header, vec!["Файл", "Поле", "Значение"]);
+        assert_eq!(
+            sheets[0].rows,
+            vec![vec![json!("a.txt"), json!("inn"), json!("7701")]]
+        );
+        assert_eq!(sheets[1].name, "Таблица 1");
+        assert_eq!(sheets[1].header, vec!["Файл", "n"]);
+        assert_eq!(
+            sheets[1].rows,
+            vec![
+                vec![json!("a.txt"), json!("1")],
+                vec![json!("b.txt"), json!("2")]
+            ]
+        );
+        assert_eq!(sheets[2].name, "Таблица 2");
+        assert_eq!(sheets[2].rows, vec![vec![json!("a.txt"), json!("x")]]);
+    }
+
+    #[test]
+    fn csv_quotes_and_decimal_comma() {
+        let sheet = Sheet {
+            name: "Сводка".to_string(),
+            header: vec!["Поле".to_string(), "Значение".to_string()],
+            rows: vec![
+                vec![json!("sum"), json!(1234.56)],
+                vec![json!("a;b"), json!("say \"hi\"")],
+                vec![json!("line"), json!("1\n2")],
+                vec![json!("empty"), Value::Null],
+            ],
+        };
+        assert_eq!(
+            to_csv(&sheet),
+            "\u{feff}Поле;Значение\r\n\
+             sum;1234,56\r\n\
+             \"a;b\";\"say \"\"hi\"\"\"\r\n\
+             line;\"1\n2\"\r\n\
+             empty;\r\n"
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::parse::{FieldSpec, Requirement, ValueType};
+
+#[derive(Properties, PartialEq)]
+pub struct FieldEditorProps {
+    pub names: Vec<String>,
+    pub fields: Vec<FieldSpec>,
+    pub on_change: Callback<Vec<FieldSpec>>,
+}
+
+const VALUE_TYPES: [(ValueType, &str); 6] = [
+    (ValueType::String, "Строка"),
+    (ValueType::Integer, "Целое число"),
+    (ValueType::Decimal, "Дробное число"),
+    (ValueType::Boolean, "Да/нет"),
+    (ValueType::Date, "Дата"),
+    (ValueType::DateTime, "Дата и время"),
+];
+
+const REQUIREMENTS: [(Requirement, &str); 3] = [
+    (Requirement::Required, "Обязательное"),
+    (Requirement::Optional, "Необязательное"),
+    (Requirement::InfoOnly, "Для информации"),
+];
+
+pub fn field_label(spec: &FieldSpec) -> String {
+    let type_label = VALUE_TYPES
+        .iter()
+        .find(|(t, _)| *t == spec.value_type)
+        .map(|(_, l)| l.to_lowercase())
+        .unwrap_or_default();
+    let requirement_label = REQUIREMENTS
+        .iter()
+        .find(|(r, _)| *r == spec.requirement)
+        .map(|(_, l)| l.to_lowercase())
+        .unwrap_or_default();
+    match &spec.format {
+        Some(f) => format!(
+            "{}: {} ({}), {}",
+            spec.name, type_label, f, requirement_label
+        ),
+        None => format!("{}: {}, {}", spec.name, type_label, requirement_label),
+    }
+}
+
+fn spec_for(fields: &[FieldSpec], name: &str) -> FieldSpec {
+    fields
+        .iter()
+        .find(|f| f.name == name)
+        .cloned()
+        .unwrap_or(FieldSpec {
+            name: name.to_string(),
+            value_type: ValueType::String,
+            format: None,
+            requirement: Requirement::Optional,
+        })
+}
+
+// Хранит только настроенные поля, чтобы не раздувать сохранённый сценарий
+fn with_spec(fields: &[FieldSpec], spec: FieldSpec) -> Vec<FieldSpec> {
+    let mut l_fields: Vec<FieldSpec> = fields
+        .iter()
+        .filter(|f| f.name != spec.name)
+        .cloned()
+        .collect();
+    if spec.value_type != ValueType::String
+        || spec.format.is_some()
+        || spec.requirement != Requirement::Optional
+    {
+        l_fields.push(spec);
+    }
+    l_fields
+}
+
+#[function_component]
+pub fn FieldEditor(props: &FieldEditorProps) -> Html {
+    let on_type = |name: &str| {
+        let fields = props.fields.clone();
+        let on_change = props.on_change.clone();
+        let name = name.to_string();
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlSelectElement>();
+            let mut spec = spec_for(&fields, &name);
+            if let Ok(index) = input.value().parse::<usize>() {
+                spec.value_type = VALUE_TYPES[index].0.clone();
+            }
+            on_change.emit(with_spec(&fields, spec));
+        })
+    };
+
+    let on_requirement = |name: &str| {
+        let fields = props.fields.clone();
+        let on_change = props.on_change.clone();
+        let name = name.to_string();
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlSelectElement>();
+            let mut spec = spec_for(&fields, &name);
+            if let Ok(index) = input.value().parse::<usize>() {
+                spec.requirement = REQUIREMENTS[index].0;
+            }
+            on_change.emit(with_spec(&fields, spec));
+        })
+    };
+
+    let on_format = |name: &str| {
+        let fields = props.fields.clone();
+        let on_change = props.on_change.clone();
+        let name = name.to_string();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let mut spec = spec_for(&fields, &name);
+                let value = input.value();
+                spec.format = if value.trim().is_empty() {
+                    None
+                } else {
+                    Some(value)
+                };
+                on_change.emit(with_spec(&fields, spec));
+            }
+        })
+    };
+
+    html! {
+        <div class="fields">
+            {
+                props.names.iter().filter(|n| !n.is_empty()).map(|name| {
+                    let spec = spec_for(&props.fields, name);
+                    let with_format = matches!(spec.value_type, ValueType::Date | ValueType::DateTime);
+                    html! {
+                        <div class="field-item">
+                            <span>{name.clone()}</span>
+                            <select onchange={on_type(name)}>
+                                {
+                                    VALUE_TYPES.iter().enumerate().map(|(index, (t, label))| html! {
+                                        <option value={format!("{}", index)} selected={*t == spec.value_type}>{*label}</option>
+                                    }).collect::<Html>()
+                                }
+                            </select>
+                            <select onchange={on_requirement(name)}>
+                                {
+                                    REQUIREMENTS.iter().enumerate().map(|(index, (r, label))| html! {
+                                        <option value={format!("{}", index)} selected={*r == spec.requirement}>{*label}</option>
+                                    }).collect::<Html>()
+                                }
+                            </select>
+                            if with_format {
+                                <input type="text" placeholder="Формат, например %d.%m.%Y" value={spec.format.clone().unwrap_or_default()} oninput={on_format(name)}/>
+                            }
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
@@ -1,12 +1,40 @@
-use gloo_file::{callbacks::read_as_text, Blob, File};
+use gloo_file::{callbacks::read_as_text, Blob, BlobContents, File};
 use gloo_utils::document;
 use web_sys::{
-    wasm_bindgen::JsCast, window, DragEvent, HtmlAnchorElement, HtmlElement, HtmlInputElement, Url,
+    wasm_bindgen::JsCast, DragEvent, HtmlAnchorElement, HtmlElement, HtmlInputElement,
+    HtmlSelectElement, HtmlTextAreaElement, Url,
 };
 use yew::prelude::*;
 
-mod parse;
-use parse::{parse, Scen, ScenItem};
+mod alternatives_editor;
+mod batch;
+mod diagnostics;
+mod export;
+mod field_editor;
+mod results;
+mod transform_editor;
+mod viewer;
+use alternatives_editor::AlternativesEditor;
+use batch::{read_files, Batch, BatchAction, BatchDocument, BatchPanel, DocumentState, LoadedDocument};
+use diagnostics::{LoadDiagnostics, LoadError};
+use field_editor::{field_label, FieldEditor};
+use gloo_timers::callback::Timeout;
+use gloo_worker::{Spawnable, WorkerBridge};
+use json_editor::worker;
+use json_editor_engine::{lint, migrate, parse, schema, transform};
+use lint::{capture_groups, lint, lint_item, regex_syntax_error};
+use migrate::{MigrationReport, FORMAT_VERSION};
+use parse::{
+    load_project, ErrorEnum, ErrorItem, FieldSpec, MatchMode, ParseOutput, ProjectFile,
+    ProjectMeta, Scen, ScenItem,
+};
+use results::ResultsPanel;
+use schema::{output_schema, project_schema, scen_item_schema};
+use schemars::schema::RootSchema;
+use transform::Transform;
+use transform_editor::{transform_label, TransformEditor};
+use viewer::{collect_marks, TextViewer};
+use worker::{JsonCodec, ParseWorker, WorkerInput, WorkerOutput};
 
 fn input_string(state: yew::UseStateHandle<String>) -> Callback<InputEvent> {
     Callback::from(move |e: InputEvent| {
@@ -16,24 +44,335 @@ fn input_string(state: yew::UseStateHandle<String>) -> Callback<InputEvent> {
     })
 }
 
+const LIVE_DELAY_MS: u32 = 400;
+
+// Путь относительно страницы: сайт публикуется не в корне домена
+const WORKER_PATH: &str = "worker.js";
+
+#[derive(Clone, Copy, PartialEq)]
+enum JobKind {
+    // «Тест» и «Тест всех сценариев»: показываются ход разбора и кнопка отмены
+    Test,
+    Live,
+    Saved,
+    // номер документа пакетной обработки
+    Batch(usize),
+}
+
+struct Job {
+    id: u64,
+    kind: JobKind,
+    scens: Vec<ScenItem>,
+    show: bool,
+    // текст, по которому считается подсветка: смещения результата действительны только для него
+    source: Option<String>,
+}
+
+// Задания, отправленные в воркер; новое задание того же вида отменяет предыдущее
+#[derive(Default)]
+struct Jobs {
+    next: u64,
+    running: Vec<Job>,
+}
+
+impl Jobs {
+    fn cancel(&mut self, bridge: &WorkerBridge<ParseWorker>, kind: JobKind) {
+        self.cancel_where(bridge, |k| k == kind);
+    }
+
+    fn cancel_where(&mut self, bridge: &WorkerBridge<ParseWorker>, f: impl Fn(JobKind) -> bool) {
+        for job in self.running.iter().filter(|j| f(j.kind)) {
+            bridge.send(WorkerInput::Cancel { job: job.id });
+        }
+        self.running.retain(|j| !f(j.kind));
+    }
+
+    fn submit(
+        &mut self,
+        bridge: &WorkerBridge<ParseWorker>,
+        kind: JobKind,
+        show: bool,
+        file_name: String,
+        text: String,
+        scens: Vec<ScenItem>,
+    ) {
+        self.cancel(bridge, kind);
+        self.next += 1;
+        self.running.push(Job {
+            id: self.next,
+            kind,
+            scens: scens.clone(),
+            show,
+            source: matches!(kind, JobKind::Live | JobKind::Saved).then(|| text.clone()),
+        });
+        bridge.send(WorkerInput::Submit {
+            job: self.next,
+            priority: !matches!(kind, JobKind::Batch(_)),
+            file_name,
+            text,
+            scens,
+        });
+    }
+
+    fn take(&mut self, id: u64) -> Option<Job> {
+        let position = self.running.iter().position(|j| j.id == id)?;
+        Some(self.running.remove(position))
+    }
+
+    fn is_running(&self, id: u64, kind: JobKind) -> bool {
+        self.running.iter().any(|j| j.id == id && j.kind == kind)
+    }
+}
+
+fn download(name: &str, data: impl BlobContents, mime_type: &str) {
+    let blob = Blob::new_with_options(data, Some(mime_type));
+    let url = gloo_file::ObjectUrl::from(blob);
+    let document = document();
+    if let Ok(anchor) = document.create_element("a") {
+        if let Ok(anchor_d) = anchor.dyn_into::<HtmlAnchorElement>() {
+            anchor_d.set_href(&url);
+            anchor_d.set_download(name);
+            document.body().unwrap().append_child(&anchor_d).unwrap();
+            anchor_d.click();
+            document.body().unwrap().remove_child(&anchor_d).unwrap();
+
+            Url::revoke_object_url(&url).unwrap();
+        };
+    };
+}
+
+fn split_names(names: &str) -> Vec<String> {
+    names.trim().split(',').map(|s| s.to_string()).collect()
+}
+
+fn lint_list<'a>(items: impl Iterator<Item = &'a ErrorItem>) -> Html {
+    let items: Vec<&ErrorItem> = items.collect();
+    if items.is_empty() {
+        return html! {};
+    }
+    html! {
+        <ul class="lint">
+            {
+                items.iter().map(|e| {
+                    let class = match e.type_error {
+                        ErrorEnum::Error => "severity-error",
+                        ErrorEnum::Warning => "severity-warning",
+                        ErrorEnum::Info => "severity-info",
+                    };
+                    html! {
+                        <li class={class}>
+                            {e.message.clone()}
+                            if let Some(detail) = &e.detail {
+                                <div class="lint-detail">{detail.clone()}</div>
+                            }
+                        </li>
+                    }
+                }).collect::<Html>()
+            }
+        </ul>
+    }
+}
+
+fn mode_to_str(mode: &MatchMode) -> &'static str {
+    match mode {
+        MatchMode::First => "first",
+        MatchMode::Last => "last",
+        MatchMode::Nth(_) => "nth",
+        MatchMode::All => "all",
+    }
+}
+
+fn mode_from_str(mode: &str, nth: usize) -> MatchMode {
+    match mode {
+        "last" => MatchMode::Last,
+        "nth" => MatchMode::Nth(nth),
+        "all" => MatchMode::All,
+        _ => MatchMode::First,
+    }
+}
+
+fn mode_label(mode: &MatchMode) -> String {
+    match mode {
+        MatchMode::First => "первое".to_string(),
+        MatchMode::Last => "последнее".to_string(),
+        MatchMode::Nth(n) => format!("по номеру {}", n),
+        MatchMode::All => "все".to_string(),
+    }
+}
+
 #[function_component]
 fn App() -> Html {
     let file_content = use_state(|| None::<String>);
-    let file_text = use_state(|| String::new());
-    let file_name = use_state(|| String::new());
-    let scens: UseStateHandle<Vec<ScenItem>> = use_state(|| vec![]);
+    let file_text = use_state(String::new);
+    let file_name = use_state(String::new);
+    let scens: UseStateHandle<Vec<ScenItem>> = use_state(Vec::new);
     let focus = use_state(|| -1);
+    let save_name_input = use_state(String::new);
+    let description_input = use_state(String::new);
+    let load_error: UseStateHandle<Option<LoadError>> = use_state(|| None);
+    let migration_report: UseStateHandle<Option<MigrationReport>> = use_state(|| None);
+    let results: UseStateHandle<Option<ParseOutput>> = use_state(|| None);
+    let live_input = use_state(|| true);
+    let live_output: UseStateHandle<Option<(String, ScenItem, ParseOutput)>> = use_state(|| None);
+    let saved_output: UseStateHandle<Option<(String, Vec<ScenItem>, ParseOutput)>> =
+        use_state(|| None);
+    let progress: UseStateHandle<Option<(usize, usize)>> = use_state(|| None);
+    let jobs = use_mut_ref(Jobs::default);
+    let batch = use_reducer(Batch::default);
+
+    let worker_bridge = {
+        let jobs = jobs.clone();
+        let results = results.clone();
+        let live_output = live_output.clone();
+        let saved_output = saved_output.clone();
+        let progress = progress.clone();
+        let batch = batch.dispatcher();
+        use_memo((), move |_| {
+            ParseWorker::spawner()
+                .encoding::<JsonCodec>()
+                .callback(move |output| match output {
+                    WorkerOutput::Progress { job, done, total } => {
+                        if jobs.borrow().is_running(job, JobKind::Test) {
+                            progress.set(Some((done, total)));
+                        }
+                    }
+                    WorkerOutput::Result { job, output } => {
+                        // результат отменённого или заменённого задания не показывается
+                        let Some(finished) = jobs.borrow_mut().take(job) else {
+                            return;
+                        };
+                        if finished.show {
+                            results.set(Some(output.clone()));
+                        }
+                        match finished.kind {
+                            JobKind::Test => progress.set(None),
+                            JobKind::Live => live_output.set(
+                                finished
+                                    .scens
+                                    .into_iter()
+                                    .next()
+                                    .map(|item| (finished.source.unwrap_or_default(), item, output)),
+                            ),
+                            JobKind::Saved => saved_output.set(Some((
+                                finished.source.unwrap_or_default(),
+                                finished.scens,
+                                output,
+                            ))),
+                            JobKind::Batch(index) => batch.dispatch(BatchAction::Done(index, output)),
+                        }
+                    }
+                    WorkerOutput::Cancelled { .. } => {}
+                })
+                .spawn(WORKER_PATH)
+        })
+    };
 
-    let regex_input = use_state(|| String::new());
-    let names_input = use_state(|| String::new());
+    let regex_input = use_state(String::new);
+    let names_input = use_state(String::new);
     let table_input = use_state(|| false);
     let file_name_bool_input = use_state(|| false);
-    let position_input = use_state(|| 0 as usize);
-    let table_mask_input = use_state(|| String::new());
+    let position_input = use_state(|| 0_usize);
+    let table_mask_input = use_state(String::new);
+    let mode_input = use_state(|| "first".to_string());
+    let nth_input = use_state(|| 0_usize);
+    let transforms_input: UseStateHandle<Vec<Transform>> = use_state(Vec::new);
+    let alternatives_input: UseStateHandle<Vec<String>> = use_state(Vec::new);
+    let fields_input: UseStateHandle<Vec<FieldSpec>> = use_state(Vec::new);
+
+    let known_groups: UseStateHandle<Vec<String>> = use_state(Vec::new);
 
-    let on_regex_input = input_string(regex_input.clone());
+    // Новые именованные группы сразу попадают в имена, исчезнувшие — убираются
+    let on_regex_input = {
+        let regex_input = regex_input.clone();
+        let names_input = names_input.clone();
+        let known_groups = known_groups.clone();
+        let alternatives_input = alternatives_input.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let value = input.value();
+                if let Some(groups) = capture_groups(&value) {
+                    // группы запасных выражений не убираем, даже если их нет в основном
+                    let alternative_groups: Vec<String> = alternatives_input
+                        .iter()
+                        .flat_map(|a| capture_groups(a).unwrap_or_default())
+                        .collect();
+                    let mut names: Vec<String> = split_names(&names_input)
+                        .into_iter()
+                        .filter(|n| !n.is_empty())
+                        .filter(|n| {
+                            groups.contains(n)
+                                || alternative_groups.contains(n)
+                                || !known_groups.contains(n)
+                        })
+                        .collect();
+                    for g in groups.iter().filter(|g| !known_groups.contains(g)) {
+                        if !names.contains(g) {
+                            names.push(g.clone());
+                        }
+                    }
+                    names.sort_by_key(|n| groups.iter().position(|g| g == n).unwrap_or(usize::MAX));
+                    names_input.set(names.join(","));
+                    known_groups.set(groups);
+                }
+                regex_input.set(value);
+            }
+        })
+    };
+
+    let on_toggle_group = |group: &str| {
+        let names_input = names_input.clone();
+        let known_groups = known_groups.clone();
+        let group = group.to_string();
+        Callback::from(move |_: MouseEvent| {
+            let mut names: Vec<String> = split_names(&names_input)
+                .into_iter()
+                .filter(|n| !n.is_empty())
+                .collect();
+            if let Some(position) = names.iter().position(|n| *n == group) {
+                names.remove(position);
+            } else {
+                names.push(group.clone());
+                names.sort_by_key(|n| {
+                    known_groups
+                        .iter()
+                        .position(|g| g == n)
+                        .unwrap_or(usize::MAX)
+                });
+            }
+            names_input.set(names.join(","));
+        })
+    };
     let on_names_input = input_string(names_input.clone());
+    // Группы основного и запасных выражений, затем имена, которых нет ни в одной группе
+    let name_chips: Vec<(String, bool)> = {
+        let mut groups: Vec<String> = (*known_groups).clone();
+        for alternative in alternatives_input.iter() {
+            for g in capture_groups(alternative).unwrap_or_default() {
+                if !groups.contains(&g) {
+                    groups.push(g);
+                }
+            }
+        }
+        let unknown: Vec<String> = split_names(&names_input)
+            .into_iter()
+            .filter(|n| !n.is_empty() && !groups.contains(n))
+            .collect();
+        groups
+            .into_iter()
+            .map(|g| (g, true))
+            .chain(unknown.into_iter().map(|n| (n, false)))
+            .collect()
+    };
     let on_table_mask_input = input_string(table_mask_input.clone());
+    let on_save_name_input = input_string(save_name_input.clone());
+    let on_description_input = {
+        let description_input = description_input.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlTextAreaElement>() {
+                description_input.set(input.value());
+            }
+        })
+    };
 
     let on_table_input = {
         let table_input = table_input.clone();
@@ -47,7 +386,7 @@ fn App() -> Html {
         let file_name_bool_input = file_name_bool_input.clone();
         Callback::from(move |event: Event| {
             let input = event.target_unchecked_into::<HtmlInputElement>();
-                file_name_bool_input.set(input.checked());
+            file_name_bool_input.set(input.checked());
         })
     };
 
@@ -64,36 +403,121 @@ fn App() -> Html {
         })
     };
 
+    let on_live_input = {
+        let live_input = live_input.clone();
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            live_input.set(input.checked());
+        })
+    };
+
+    let on_mode_input = {
+        let mode_input = mode_input.clone();
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlSelectElement>();
+            mode_input.set(input.value());
+        })
+    };
+
+    let on_nth_input = {
+        let nth_input = nth_input.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                if let Ok(u) = input.value().trim().parse::<usize>() {
+                    nth_input.set(u);
+                } else {
+                    nth_input.set(0);
+                };
+            }
+        })
+    };
+
+    let on_transforms_change = {
+        let transforms_input = transforms_input.clone();
+        Callback::from(move |transforms: Vec<Transform>| {
+            transforms_input.set(transforms);
+        })
+    };
+
+    let on_alternatives_change = {
+        let alternatives_input = alternatives_input.clone();
+        Callback::from(move |alternatives: Vec<String>| {
+            alternatives_input.set(alternatives);
+        })
+    };
+
+    let on_fields_change = {
+        let fields_input = fields_input.clone();
+        Callback::from(move |fields: Vec<FieldSpec>| {
+            fields_input.set(fields);
+        })
+    };
+
     let ondragover = Callback::from(|event: DragEvent| {
         event.prevent_default();
     });
 
-    let read_file = {
+    let on_loaded = {
         let file_content = file_content.clone();
         let file_text = file_text.clone();
         let file_name = file_name.clone();
         let scens = scens.clone();
+        let save_name_input = save_name_input.clone();
+        let description_input = description_input.clone();
+        let load_error = load_error.clone();
+        let migration_report = migration_report.clone();
+        Callback::from(move |(loaded_name, text): (String, String)| {
+            file_content.set(Some(text.clone()));
+            match load_project(&text) {
+                Ok((project, report)) => {
+                    migration_report.set(if report.changes.is_empty() {
+                        None
+                    } else {
+                        Some(report)
+                    });
+                    match project {
+                        ProjectFile::Full(v) => {
+                            file_name.set(v.file_name);
+                            file_text.set(v.text);
+                            if let Some(scens_l) = v.scen {
+                                scens.set(scens_l);
+                            };
+                            description_input.set(v.meta.description);
+                        }
+                        ProjectFile::Scens(scens_l) => {
+                            scens.set(scens_l);
+                        }
+                    }
+                    save_name_input.set(loaded_name);
+                    load_error.set(None);
+                }
+                Err(e) => {
+                    load_error.set(Some(LoadError::from_serde(&loaded_name, &e)));
+                }
+            }
+        })
+    };
+
+    let read_file = {
+        let on_loaded = on_loaded.clone();
+        let file_content = file_content.clone();
+        let load_error = load_error.clone();
         move |file: File| {
+            let loaded_name = file.name();
             let file_reader = read_as_text(&file, {
+                let on_loaded = on_loaded.clone();
                 let file_content = file_content.clone();
-                let file_name = file_name.clone();
-                let file_text = file_text.clone();
-                let scens = scens.clone();
-                move |result| {
-                    if let Ok(text) = result {
-                        file_content.set(Some(text.clone()));
-                        match serde_json::from_str::<Scen>(&text) {
-                            Ok(v) => {
-                                file_name.set(v.file_name);
-                                file_text.set(v.text);
-                                if let Some(scens_l) = v.scen {
-                                    scens.set(scens_l);
-                                };
-                            }
-                            Err(_) => {}
-                        }
-                        // let jsv: serde_json::Value = serde_json::from_str(&text);
-                        // file_text.set(text.clone());
+                let load_error = load_error.clone();
+                move |result| match result {
+                    Ok(text) => on_loaded.emit((loaded_name, text)),
+                    Err(e) => {
+                        file_content.set(None);
+                        load_error.set(Some(LoadError {
+                            file: loaded_name,
+                            message: format!("Не удалось прочитать файл как текст: {}", e),
+                            line: 0,
+                            column: 0,
+                        }));
                     }
                 }
             });
@@ -102,6 +526,33 @@ fn App() -> Html {
         }
     };
 
+    let on_apply_fixed = {
+        let on_loaded = on_loaded.clone();
+        let save_name_input = save_name_input.clone();
+        let load_error = load_error.clone();
+        Callback::from(move |text: String| {
+            let name = match &*load_error {
+                Some(e) => e.file.clone(),
+                None => (*save_name_input).clone(),
+            };
+            on_loaded.emit((name, text));
+        })
+    };
+
+    let on_close_migration_report = {
+        let migration_report = migration_report.clone();
+        Callback::from(move |_| {
+            migration_report.set(None);
+        })
+    };
+
+    let on_close_load_error = {
+        let load_error = load_error.clone();
+        Callback::from(move |_| {
+            load_error.set(None);
+        })
+    };
+
     let ondrop = {
         let read_file = read_file.clone();
         Callback::from(move |event: DragEvent| {
@@ -137,87 +588,276 @@ fn App() -> Html {
             }
         })
     };
-    let on_click_save = {
-        let regex_input = regex_input.clone();
-        let names_input = names_input.clone();
-        let file_name_bool_input = file_name_bool_input.clone();
-        let table_input = table_input.clone();
-        let position_input = position_input.clone();
-        let table_mask_input = table_mask_input.clone();
+    let on_batch_read = {
         let scens = scens.clone();
-        let focus = focus.clone();
-        let mut scen_vec: Vec<ScenItem> = (*scens).clone();
+        let jobs = jobs.clone();
+        let worker_bridge = worker_bridge.clone();
+        let batch = batch.dispatcher();
+        Callback::from(move |loaded: Vec<LoadedDocument>| {
+            let mut l_jobs = jobs.borrow_mut();
+            l_jobs.cancel_where(&worker_bridge, |k| matches!(k, JobKind::Batch(_)));
+            let mut documents = vec![];
+            for (index, (name, document)) in loaded.into_iter().enumerate() {
+                let state = match document {
+                    Ok((file_name, text)) => {
+                        l_jobs.submit(
+                            &worker_bridge,
+                            JobKind::Batch(index),
+                            false,
+                            file_name,
+                            text,
+                            (*scens).clone(),
+                        );
+                        DocumentState::Pending
+                    }
+                    Err(message) => DocumentState::Failed(message),
+                };
+                documents.push(BatchDocument { name, state });
+            }
+            batch.dispatch(BatchAction::Start(documents));
+        })
+    };
 
-        let names = (*names_input)
-            .trim()
-            .split(',')
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>();
+    let on_batch_drop = {
+        let on_batch_read = on_batch_read.clone();
+        Callback::from(move |event: DragEvent| {
+            event.prevent_default();
 
-        let mut tm: Option<String> = None;
+            if let Some(files) = event.data_transfer().and_then(|dt| dt.files()) {
+                let files: Vec<File> = (0..files.length())
+                    .filter_map(|i| files.get(i))
+                    .map(File::from)
+                    .collect();
+                if !files.is_empty() {
+                    read_files(files, on_batch_read.clone());
+                }
+            };
+        })
+    };
 
-        if *table_input {
-            tm = Some((*table_mask_input).clone());
-        }
-        let item = ScenItem {
-            regex: (*regex_input).clone(),
-            names: names,
-            file_name_bool: *file_name_bool_input,
-            table: *table_input,
-            position: *position_input,
-            table_mask: tm,
-        };
-        if *focus < 0 {
-            scen_vec.push(item);
-        } else {
-            scen_vec[*focus as usize] = item.clone();
-        }
+    let batch_input_ref = use_node_ref();
 
+    let on_batch_change = {
+        let batch_input_ref = batch_input_ref.clone();
+        let on_batch_read = on_batch_read.clone();
         Callback::from(move |_| {
-            scens.set(scen_vec.clone());
+            if let Some(input) = batch_input_ref.cast::<HtmlInputElement>() {
+                if let Some(files) = input.files() {
+                    let files: Vec<File> = (0..files.length())
+                        .filter_map(|i| files.get(i))
+                        .map(File::from)
+                        .collect();
+                    if !files.is_empty() {
+                        read_files(files, on_batch_read.clone());
+                    }
+                }
+                // чтобы повторный выбор тех же файлов снова вызвал onchange
+                input.set_value("");
+            }
         })
     };
 
-    let on_click_test = {
-        let regex_input = regex_input.clone();
-        let names_input = names_input.clone();
-        let file_name_bool_input = file_name_bool_input.clone();
-        let table_input = table_input.clone();
-        let position_input = position_input.clone();
-        let table_mask_input = table_mask_input.clone();
-        let file_text = file_text.clone();
-        let file_name = file_name.clone();
+    let on_batch_click = {
+        let batch_input_ref = batch_input_ref.clone();
+        Callback::from(move |_| {
+            if let Some(input) = batch_input_ref.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        })
+    };
+
+    let on_batch_cancel = {
+        let jobs = jobs.clone();
+        let worker_bridge = worker_bridge.clone();
+        let batch = batch.dispatcher();
+        Callback::from(move |_| {
+            jobs.borrow_mut()
+                .cancel_where(&worker_bridge, |k| matches!(k, JobKind::Batch(_)));
+            batch.dispatch(BatchAction::Cancel);
+        })
+    };
+
+    let on_batch_close = {
+        let jobs = jobs.clone();
+        let worker_bridge = worker_bridge.clone();
+        let batch = batch.dispatcher();
+        Callback::from(move |_| {
+            jobs.borrow_mut()
+                .cancel_where(&worker_bridge, |k| matches!(k, JobKind::Batch(_)));
+            batch.dispatch(BatchAction::Clear);
+        })
+    };
 
-        let names = (*names_input)
-            .trim()
-            .split(',')
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>();
+    let current_item = {
+        let names = split_names(&names_input);
 
         let mut tm: Option<String> = None;
 
         if *table_input {
             tm = Some((*table_mask_input).clone());
         }
-        let item = ScenItem {
+        ScenItem {
             regex: (*regex_input).clone(),
-            names: names,
+            // пустые поля, оставшиеся после «Добавить», не сохраняются
+            alternatives: alternatives_input
+                .iter()
+                .filter(|a| !a.trim().is_empty())
+                .cloned()
+                .collect(),
             file_name_bool: *file_name_bool_input,
             table: *table_input,
             position: *position_input,
             table_mask: tm,
-        };
+            mode: mode_from_str(mode_input.as_str(), *nth_input),
+            transforms: (*transforms_input).clone(),
+            fields: (*fields_input)
+                .iter()
+                .filter(|f| names.contains(&f.name))
+                .cloned()
+                .collect(),
+            names,
+        }
+    };
 
-        let out = parse((*file_name).clone(), (*file_text).clone(), vec![item]);
+    // Текущий элемент формы проверяется с задержкой, чтобы не разбирать текст на каждое нажатие
+    {
+        let live_output = live_output.clone();
+        let results = results.clone();
+        let jobs = jobs.clone();
+        let worker_bridge = worker_bridge.clone();
+        use_effect_with(
+            (
+                current_item.clone(),
+                (*file_name).clone(),
+                (*file_text).clone(),
+                *live_input,
+            ),
+            move |(item, file_name, file_text, live)| {
+                let item = item.clone();
+                let file_name = file_name.clone();
+                let file_text = file_text.clone();
+                let live = *live;
+                let timeout = Timeout::new(LIVE_DELAY_MS, move || {
+                    if item.regex.is_empty() {
+                        jobs.borrow_mut().cancel(&worker_bridge, JobKind::Live);
+                        if live {
+                            results.set(None);
+                        }
+                        live_output.set(None);
+                    } else {
+                        jobs.borrow_mut().submit(
+                            &worker_bridge,
+                            JobKind::Live,
+                            live,
+                            file_name,
+                            file_text,
+                            vec![item],
+                        );
+                    }
+                });
+                move || drop(timeout)
+            },
+        );
+    }
+
+    {
+        let jobs = jobs.clone();
+        let worker_bridge = worker_bridge.clone();
+        use_effect_with(
+            (
+                (*file_name).clone(),
+                (*file_text).clone(),
+                (*scens).clone(),
+            ),
+            move |(file_name, file_text, scens)| {
+                jobs.borrow_mut().submit(
+                    &worker_bridge,
+                    JobKind::Saved,
+                    false,
+                    file_name.clone(),
+                    file_text.clone(),
+                    scens.clone(),
+                );
+            },
+        );
+    }
 
-        
-        let js = serde_json::to_string(&{out}).unwrap();
+    // результаты, посчитанные для другого текста (документ уже сменился), не подсвечиваются
+    let highlights = use_memo(
+        (
+            (*file_text).clone(),
+            (*saved_output).clone(),
+            (*live_output).clone(),
+        ),
+        |(file_text, saved_output, live_output)| {
+            let mut marks = vec![];
+            let mut blocks = vec![];
+            if let Some((_, scens, (singles, tables, _))) =
+                saved_output.as_ref().filter(|(source, _, _)| source == file_text)
+            {
+                collect_marks(scens, singles, tables, &mut marks, &mut blocks);
+            }
+            if let Some((_, item, (singles, tables, _))) =
+                live_output.as_ref().filter(|(source, _, _)| source == file_text)
+            {
+                collect_marks(
+                    std::slice::from_ref(item),
+                    singles,
+                    tables,
+                    &mut marks,
+                    &mut blocks,
+                );
+            }
+            (marks, blocks)
+        },
+    );
+
+    let regex_error = use_memo((*regex_input).clone(), |regex| regex_syntax_error(regex));
+
+    let lint_items = use_memo(current_item.clone(), |item| {
+        if item.regex.is_empty() {
+            vec![]
+        } else {
+            lint_item(0, item)
+        }
+    });
+
+    let saved_lint_items = use_memo((*scens).clone(), |scens| lint(scens));
+
+    let on_click_save = {
+        let scens = scens.clone();
+        let focus = focus.clone();
+        let item = current_item.clone();
 
         Callback::from(move |_| {
-            if let Some(win) = window() {
-                win.alert_with_message(format!("{}", js).as_str())
-                    .expect("");
+            let mut scen_vec: Vec<ScenItem> = (*scens).clone();
+            if *focus < 0 {
+                scen_vec.push(item.clone());
+            } else {
+                scen_vec[*focus as usize] = item.clone();
             }
+            scens.set(scen_vec);
+        })
+    };
+
+    let on_click_test = {
+        let file_text = file_text.clone();
+        let file_name = file_name.clone();
+        let progress = progress.clone();
+        let jobs = jobs.clone();
+        let worker_bridge = worker_bridge.clone();
+        let item = current_item.clone();
+
+        Callback::from(move |_| {
+            jobs.borrow_mut().submit(
+                &worker_bridge,
+                JobKind::Test,
+                true,
+                (*file_name).clone(),
+                (*file_text).clone(),
+                vec![item.clone()],
+            );
+            progress.set(Some((0, 1)));
         })
     };
 
@@ -225,17 +865,38 @@ fn App() -> Html {
         let scens = scens.clone();
         let file_text = file_text.clone();
         let file_name = file_name.clone();
+        let progress = progress.clone();
+        let jobs = jobs.clone();
+        let worker_bridge = worker_bridge.clone();
+
+        Callback::from(move |_| {
+            jobs.borrow_mut().submit(
+                &worker_bridge,
+                JobKind::Test,
+                true,
+                (*file_name).clone(),
+                (*file_text).clone(),
+                (*scens).clone(),
+            );
+            progress.set(Some((0, scens.len())));
+        })
+    };
 
-        let out = parse((*file_name).clone(), (*file_text).clone(), (*scens).clone());
+    let on_cancel_test = {
+        let progress = progress.clone();
+        let jobs = jobs.clone();
+        let worker_bridge = worker_bridge.clone();
 
-        
-        let js = serde_json::to_string(&{out}).unwrap();
+        Callback::from(move |_| {
+            jobs.borrow_mut().cancel(&worker_bridge, JobKind::Test);
+            progress.set(None);
+        })
+    };
 
+    let on_close_results = {
+        let results = results.clone();
         Callback::from(move |_| {
-            if let Some(win) = window() {
-                win.alert_with_message(format!("{}", js).as_str())
-                    .expect("");
-            }
+            results.set(None);
         })
     };
 
@@ -246,15 +907,27 @@ fn App() -> Html {
         let table_input = table_input.clone();
         let position_input = position_input.clone();
         let table_mask_input = table_mask_input.clone();
+        let mode_input = mode_input.clone();
+        let nth_input = nth_input.clone();
+        let transforms_input = transforms_input.clone();
+        let alternatives_input = alternatives_input.clone();
+        let fields_input = fields_input.clone();
+        let known_groups = known_groups.clone();
         let focus = focus.clone();
 
         Callback::from(move |_| {
             regex_input.set(String::new());
             names_input.set(String::new());
+            known_groups.set(vec![]);
             file_name_bool_input.set(false);
             table_input.set(false);
-            position_input.set(0 as usize);
+            position_input.set(0);
             table_mask_input.set(String::new());
+            mode_input.set("first".to_string());
+            nth_input.set(0);
+            transforms_input.set(vec![]);
+            alternatives_input.set(vec![]);
+            fields_input.set(vec![]);
             focus.set(-1);
         })
     };
@@ -267,6 +940,12 @@ fn App() -> Html {
         let table_input = table_input.clone();
         let position_input = position_input.clone();
         let table_mask_input = table_mask_input.clone();
+        let mode_input = mode_input.clone();
+        let nth_input = nth_input.clone();
+        let transforms_input = transforms_input.clone();
+        let alternatives_input = alternatives_input.clone();
+        let fields_input = fields_input.clone();
+        let known_groups = known_groups.clone();
         let focus = focus.clone();
         Callback::from(move |e: MouseEvent| {
             if let Some(target) = e.target_dyn_into::<HtmlElement>() {
@@ -279,12 +958,20 @@ fn App() -> Html {
                     if let Some(scen_item) = (*scens).get(step) {
                         regex_input.set(scen_item.regex.clone());
                         names_input.set(scen_item.names.join(","));
+                        known_groups.set(capture_groups(&scen_item.regex).unwrap_or_default());
                         file_name_bool_input.set(scen_item.file_name_bool);
                         table_input.set(scen_item.table);
                         position_input.set(scen_item.position);
                         if let Some(tm) = scen_item.table_mask.clone() {
                             table_mask_input.set(tm);
                         };
+                        mode_input.set(mode_to_str(&scen_item.mode).to_string());
+                        if let MatchMode::Nth(n) = scen_item.mode {
+                            nth_input.set(n);
+                        };
+                        transforms_input.set(scen_item.transforms.clone());
+                        alternatives_input.set(scen_item.alternatives.clone());
+                        fields_input.set(scen_item.fields.clone());
                         focus.set(step as i32);
                     };
                 }
@@ -312,46 +999,145 @@ fn App() -> Html {
 
     let on_save_file = {
         let scens = scens.clone();
+        let file_name = file_name.clone();
+        let file_text = file_text.clone();
+        let save_name_input = save_name_input.clone();
+        let description_input = description_input.clone();
         move |_| {
-            if let Ok(data) = serde_json::to_string_pretty(&(*scens)) {
-                let blob = Blob::new_with_options(data.as_str(), Some("application/json"));
-                let url = gloo_file::ObjectUrl::from(blob);
-                let document = document();
-                if let Ok(anchor) = document.create_element("a") {
-                    if let Ok(anchor_d) = anchor.dyn_into::<HtmlAnchorElement>() {
-                        anchor_d.set_href(&url);
-                        anchor_d.set_download("name.json");
-                        document.body().unwrap().append_child(&anchor_d).unwrap();
-                        anchor_d.click();
-                        document.body().unwrap().remove_child(&anchor_d).unwrap();
-
-                        Url::revoke_object_url(&url).unwrap();
-                    };
-                };
+            let project = Scen {
+                version: FORMAT_VERSION,
+                file_name: (*file_name).clone(),
+                text: (*file_text).clone(),
+                scen: Some((*scens).clone()),
+                meta: ProjectMeta {
+                    description: (*description_input).clone(),
+                    saved_at: Some(chrono::Local::now().to_rfc3339()),
+                },
+            };
+            let mut save_name = save_name_input.trim().to_string();
+            if save_name.is_empty() {
+                save_name = "scen.json".to_string();
+            } else if !save_name.ends_with(".json") {
+                save_name.push_str(".json");
+            }
+            if let Ok(data) = serde_json::to_string_pretty(&project) {
+                download(&save_name, data.as_str(), "application/json");
             };
         }
     };
 
+    let download_schema = |name: &'static str, schema: fn() -> RootSchema| {
+        Callback::from(move |_| {
+            if let Ok(data) = serde_json::to_string_pretty(&schema()) {
+                download(name, data.as_str(), "application/json");
+            };
+        })
+    };
+
     html! {
     <div class="container">
         <div class="left">
             <h2>{"Json редактор"}</h2>
-            <div class="upload-area" ondragover={ondragover} ondrop={ondrop} onclick={on_click}>
+            <div class="upload-area" ondragover={ondragover.clone()} ondrop={ondrop} onclick={on_click}>
                 {"Перетащите файл cюда или нажмите для выбора"}
             </div>
             <input type="file" accept=".json" ref={file_input_ref} onchange={on_file_change} style="display: none;"/>
+            <div class="upload-area" ondragover={ondragover} ondrop={on_batch_drop} onclick={on_batch_click}>
+                {"Пакетная обработка: перетащите документы или ZIP-архив сюда или нажмите для выбора"}
+            </div>
+            <input type="file" multiple=true ref={batch_input_ref} onchange={on_batch_change} style="display: none;"/>
+            if let Some(error) = (*load_error).clone() {
+                <LoadDiagnostics
+                    error={error}
+                    content={(*file_content).clone()}
+                    on_apply={on_apply_fixed}
+                    on_close={on_close_load_error}/>
+            }
+            if let Some(report) = (*migration_report).clone() {
+                <div class="migration-report">
+                    <div class="results-header">
+                        <h4>{format!("Файл обновлён с версии формата {} до {}", report.from, report.to)}</h4>
+                        <span class="entry-buttons">
+                            <button class="delete-btn" onclick={on_close_migration_report}>{"Закрыть"}</button>
+                        </span>
+                    </div>
+                    <ul>
+                        { report.changes.iter().map(|c| html! { <li>{c.clone()}</li> }).collect::<Html>() }
+                    </ul>
+                </div>
+            }
             <div class="output"><h4>{"Имя файла:"}</h4>{(*file_name).clone()}</div>
-            <div class="output"><h4>{"Текст:"}</h4>{(*file_text).clone()}</div>
+            <div class="form-group">
+                <label>{"Описание проекта:"}</label>
+                <textarea value={(*description_input).clone()} oninput={on_description_input}/>
+            </div>
+            <div class="form-group">
+                <label>{"Сохранить проект как:"}</label>
+                <input type="text" placeholder="scen.json" value={(*save_name_input).clone()} oninput={on_save_name_input}/>
+            </div>
+            <div class="output">
+                <h4>{"Текст:"}</h4>
+                <TextViewer text={(*file_text).clone()} marks={highlights.0.clone()} blocks={highlights.1.clone()}/>
+            </div>
         </div>
         <div class="right">
             <div class="form-group">
                 <label>{"Регулярное выражения:"}</label>
                 <input type="text" value={(*regex_input).clone()} oninput={on_regex_input}/>
+                if let Some(e) = (*regex_error).clone() {
+                    <div class="regex-error">
+                        <div>{format!("Ошибка в выражении (символ {}): {}", e.start + 1, e.message)}</div>
+                        <pre>{format!("{}\n{}{}", *regex_input, " ".repeat(e.start), "^".repeat((e.end - e.start).max(1)))}</pre>
+                    </div>
+                }
+            </div>
+            <div class="form-group">
+                <label>{"Запасные выражения (пробуются по порядку, если основное ничего не нашло):"}</label>
+                <AlternativesEditor alternatives={(*alternatives_input).clone()} on_change={on_alternatives_change}/>
             </div>
             <div class="form-group">
                 <label>{"Имена (через запятую):"}</label>
                 <input type="text" value={(*names_input).clone()} oninput={on_names_input}/>
+                if !name_chips.is_empty() {
+                    <div class="chips">
+                        {
+                            name_chips.iter().map(|(name, known)| {
+                                let selected = split_names(&names_input).contains(name);
+                                html! {
+                                    <button
+                                        class={classes!("chip", selected.then_some("chip-selected"), (!known).then_some("chip-unknown"))}
+                                        title={(!known).then_some("Такой группы нет ни в одном выражении")}
+                                        onclick={on_toggle_group(name)}>
+                                        {if *known { name.clone() } else { format!("{} ×", name) }}
+                                    </button>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </div>
+                }
+            </div>
+            <div class="form-group">
+                <label>{"Поля:"}</label>
+                <FieldEditor
+                    names={split_names(&names_input)}
+                    fields={(*fields_input).clone()}
+                    on_change={on_fields_change}/>
             </div>
+            <div class="form-group">
+                <label>{"Какие совпадения возвращать:"}</label>
+                <select onchange={on_mode_input}>
+                    <option value="first" selected={*mode_input == "first"}>{"Первое"}</option>
+                    <option value="last" selected={*mode_input == "last"}>{"Последнее"}</option>
+                    <option value="nth" selected={*mode_input == "nth"}>{"По номеру"}</option>
+                    <option value="all" selected={*mode_input == "all"}>{"Все"}</option>
+                </select>
+            </div>
+            if *mode_input == "nth" {
+                <div class="form-group">
+                    <label>{"Номер совпадения:"}</label>
+                    <input type="number" value={format!("{}", *nth_input)} oninput={on_nth_input}/>
+                </div>
+            }
             <div class="form-group">
                 <label>{"Поиск в имени файла:"}</label>
                 <input type="checkbox" checked={*file_name_bool_input} onchange={on_file_name_bool_input}/>
@@ -368,6 +1154,15 @@ fn App() -> Html {
                 <label>{"Регулярное выражение для поиска таблици:"}</label>
                 <input type="text" value={(*table_mask_input).clone()} oninput={on_table_mask_input}/>
             </div>
+            <div class="form-group">
+                <label>{"Проверять на лету:"}</label>
+                <input type="checkbox" checked={*live_input} onchange={on_live_input}/>
+            </div>
+            <div class="form-group">
+                <label>{"Обработка значений:"}</label>
+                <TransformEditor transforms={(*transforms_input).clone()} on_change={on_transforms_change}/>
+            </div>
+            {lint_list(lint_items.iter())}
             <div class="buttons">
                 <button class="save-btn" onclick={on_click_save}>{"Сохранить"}</button>
                 <button class="reset-btn" onclick={on_click_clear}>{"Сбросить"}</button>
@@ -375,15 +1170,48 @@ fn App() -> Html {
                 <button class="test-btn" onclick={on_click_test_all}>{"Тест всех сценариев"}</button>
                 <button class="test-btn" onclick={on_save_file}>{"Сохранить файл"}</button>
             </div>
+            if let Some((done, total)) = *progress {
+                <div class="progress">
+                    <progress value={format!("{}", done)} max={format!("{}", total)}/>
+                    <span>{format!("Обработано сценариев: {} из {}", done, total)}</span>
+                    <button class="delete-btn" onclick={on_cancel_test}>{"Отменить"}</button>
+                </div>
+            }
+            <div class="buttons">
+                <button class="test-btn" onclick={download_schema("scen.schema.json", project_schema)}>{"Схема проекта"}</button>
+                <button class="test-btn" onclick={download_schema("scen-item.schema.json", scen_item_schema)}>{"Схема сценария"}</button>
+                <button class="test-btn" onclick={download_schema("output.schema.json", output_schema)}>{"Схема результата"}</button>
+            </div>
+            if let Some(output) = (*results).clone() {
+                <ResultsPanel output={output} name={(*file_name).clone()} on_close={on_close_results}/>
+            }
+            if !batch.documents.is_empty() {
+                <BatchPanel
+                    documents={batch.documents.clone()}
+                    on_cancel={on_batch_cancel}
+                    on_close={on_batch_close}/>
+            }
             <div class="saved-list">{
                 (*scens).clone().into_iter().enumerate().map(|(index, item)| {
                     html!{
                         <div>
                             <ul>
                                 <li>{format!("Регулярное выражения: \"{}\"", item.regex)}</li>
+                                if !item.alternatives.is_empty() {
+                                    <li>{format!("Запасные выражения: {}", item.alternatives.iter().map(|a| format!("\"{}\"", a)).collect::<Vec<String>>().join(", "))}</li>
+                                }
                                 <li>{format!("Имена: [{}]", item.names.join(","))}</li>
+                                if !item.fields.is_empty() {
+                                    <li>{format!("Поля: {}", item.fields.iter().map(field_label).collect::<Vec<String>>().join(", "))}</li>
+                                }
                                 <li>{format!("Поиск в имени файла: {}", item.file_name_bool)}</li>
+                                if !item.table {
+                                    <li>{format!("Совпадения: {}", mode_label(&item.mode))}</li>
+                                }
                                 <li>{format!("Таблица: {}", item.table)}</li>
+                                if !item.transforms.is_empty() {
+                                    <li>{format!("Обработка: {}", item.transforms.iter().map(transform_label).collect::<Vec<String>>().join(", "))}</li>
+                                }
                                 if item.table {
                                     <li>{format!("Позиция: {}", item.position)}</li>
                                     if item.table_mask.is_some() {
@@ -392,6 +1220,7 @@ fn App() -> Html {
 
                                 }
                             </ul>
+                            {lint_list(saved_lint_items.iter().filter(|e| e.scen_index == index))}
 
                             <span class="entry-buttons">
                                 <button class="edit-btn" onclick={on_click_edit.clone()} data-step={format!("{}", index)}>{"Редактировать"}</button>
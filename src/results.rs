@@ -0,0 +1,126 @@
+use serde_json::Value;
+use web_sys::window;
+use yew::prelude::*;
+
+use crate::export::ExportButtons;
+use crate::parse::{ErrorEnum, ErrorItem, OutputValue, ParseOutput};
+
+#[derive(Properties, PartialEq)]
+pub struct ResultsPanelProps {
+    pub output: ParseOutput,
+    // имя документа для выгружаемых файлов
+    #[prop_or_default]
+    pub name: AttrValue,
+    pub on_close: Callback<MouseEvent>,
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "—".to_string(),
+        other => other.to_string(),
+    }
+}
+
+const SEVERITIES: [(ErrorEnum, &str, &str); 3] = [
+    (ErrorEnum::Error, "Ошибки", "severity-error"),
+    (ErrorEnum::Warning, "Предупреждения", "severity-warning"),
+    (ErrorEnum::Info, "Информация", "severity-info"),
+];
+
+#[function_component]
+pub fn ResultsPanel(props: &ResultsPanelProps) -> Html {
+    let (singles, tables, errors) = &props.output;
+
+    let on_copy = {
+        let js = serde_json::to_string_pretty(&props.output).unwrap_or_default();
+        Callback::from(move |_| {
+            if let Some(win) = window() {
+                let _ = win.navigator().clipboard().write_text(&js);
+            }
+        })
+    };
+
+    html! {
+        <div class="results">
+            <div class="results-header">
+                <h4>{"Результаты"}</h4>
+                <span class="entry-buttons">
+                    <ExportButtons documents={vec![(None, props.output.clone())]} name={props.name.clone()}/>
+                    <button class="edit-btn" onclick={on_copy}>{"Копировать как JSON"}</button>
+                    <button class="delete-btn" onclick={props.on_close.clone()}>{"Закрыть"}</button>
+                </span>
+            </div>
+            if !singles.is_empty() {
+                <table class="results-kv">
+                    {
+                        singles.iter().map(|item| html! {
+                            <tr>
+                                <th>
+                                    {item.name.clone()}
+                                    if item.alternative > 0 {
+                                        <div class="lint-detail">{format!("запасное выражение {}", item.alternative)}</div>
+                                    }
+                                </th>
+                                <td>{
+                                    match &item.value {
+                                        OutputValue::Single(v) => html! { value_to_string(v) },
+                                        OutputValue::Multiple(vs) => html! {
+                                            <ul>
+                                                { vs.iter().map(|v| html! { <li>{value_to_string(v)}</li> }).collect::<Html>() }
+                                            </ul>
+                                        },
+                                    }
+                                }</td>
+                            </tr>
+                        }).collect::<Html>()
+                    }
+                </table>
+            }
+            {
+                tables.iter().map(|table| html! {
+                    <>
+                        if table.alternative > 0 {
+                            <div class="lint-detail">{format!("Строки разобраны запасным выражением {}", table.alternative)}</div>
+                        }
+                        <table class="results-grid">
+                            <tr>
+                                { table.names.iter().map(|name| html! { <th>{name.clone()}</th> }).collect::<Html>() }
+                            </tr>
+                            {
+                                table.rows.iter().map(|row| html! {
+                                    <tr>
+                                        {
+                                            table.names.iter().map(|name| html! {
+                                                <td>{row.get(name).cloned().flatten().map(|v| value_to_string(&v)).unwrap_or("—".to_string())}</td>
+                                            }).collect::<Html>()
+                                        }
+                                    </tr>
+                                }).collect::<Html>()
+                            }
+                        </table>
+                    </>
+                }).collect::<Html>()
+            }
+            {
+                SEVERITIES.iter().map(|(severity, title, class)| {
+                    let group: Vec<&ErrorItem> = errors.iter().filter(|e| e.type_error == *severity).collect();
+                    if group.is_empty() {
+                        return html! {};
+                    }
+                    html! {
+                        <div class={classes!("results-errors", *class)}>
+                            <h4>{format!("{} ({})", title, group.len())}</h4>
+                            <ul>
+                                { group.iter().map(|e| html! { <li>{e.message.clone()}</li> }).collect::<Html>() }
+                            </ul>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+            if singles.is_empty() && tables.is_empty() && errors.is_empty() {
+                <p>{"Нет результатов"}</p>
+            }
+        </div>
+    }
+}
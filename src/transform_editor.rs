@@ -0,0 +1,162 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::transform::Transform;
+
+#[derive(Properties, PartialEq)]
+pub struct TransformEditorProps {
+    pub transforms: Vec<Transform>,
+    pub on_change: Callback<Vec<Transform>>,
+}
+
+fn new_transform(kind: &str) -> Transform {
+    match kind {
+        "collapse" => Transform::CollapseWhitespace,
+        "replace" => Transform::Replace {
+            pattern: String::new(),
+            replacement: String::new(),
+        },
+        "upper" => Transform::Uppercase,
+        "lower" => Transform::Lowercase,
+        "thousands" => Transform::StripThousands,
+        "substring" => Transform::Substring {
+            start: 0,
+            length: None,
+        },
+        _ => Transform::Trim,
+    }
+}
+
+pub fn transform_label(t: &Transform) -> String {
+    match t {
+        Transform::Trim => "обрезать пробелы".to_string(),
+        Transform::CollapseWhitespace => "схлопнуть пробелы".to_string(),
+        Transform::Replace {
+            pattern,
+            replacement,
+        } => format!("замена \"{}\" на \"{}\"", pattern, replacement),
+        Transform::Uppercase => "в верхний регистр".to_string(),
+        Transform::Lowercase => "в нижний регистр".to_string(),
+        Transform::StripThousands => "убрать разделители разрядов".to_string(),
+        Transform::Substring { start, length } => match length {
+            Some(l) => format!("подстрока с {} длиной {}", start, l),
+            None => format!("подстрока с {}", start),
+        },
+    }
+}
+
+fn set_pattern(t: &mut Transform, value: String) {
+    if let Transform::Replace { pattern, .. } = t {
+        *pattern = value;
+    }
+}
+
+fn set_replacement(t: &mut Transform, value: String) {
+    if let Transform::Replace { replacement, .. } = t {
+        *replacement = value;
+    }
+}
+
+fn set_start(t: &mut Transform, value: String) {
+    if let Transform::Substring { start, .. } = t {
+        *start = value.trim().parse::<usize>().unwrap_or(0);
+    }
+}
+
+fn set_length(t: &mut Transform, value: String) {
+    if let Transform::Substring { length, .. } = t {
+        *length = value.trim().parse::<usize>().ok();
+    }
+}
+
+#[function_component]
+pub fn TransformEditor(props: &TransformEditorProps) -> Html {
+    let kind = use_state(|| "trim".to_string());
+
+    let on_kind = {
+        let kind = kind.clone();
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlSelectElement>();
+            kind.set(input.value());
+        })
+    };
+
+    let on_add = {
+        let transforms = props.transforms.clone();
+        let on_change = props.on_change.clone();
+        let kind = kind.clone();
+        Callback::from(move |_| {
+            let mut l_transforms = transforms.clone();
+            l_transforms.push(new_transform(kind.as_str()));
+            on_change.emit(l_transforms);
+        })
+    };
+
+    let edit = |index: usize, f: fn(&mut Transform, String)| {
+        let transforms = props.transforms.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let mut l_transforms = transforms.clone();
+                f(&mut l_transforms[index], input.value());
+                on_change.emit(l_transforms);
+            }
+        })
+    };
+
+    let remove = |index: usize| {
+        let transforms = props.transforms.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut l_transforms = transforms.clone();
+            l_transforms.remove(index);
+            on_change.emit(l_transforms);
+        })
+    };
+
+    html! {
+        <div class="transforms">
+            {
+                props.transforms.iter().enumerate().map(|(index, t)| {
+                    html! {
+                        <div class="transform-item">
+                            <span>{format!("{}. {}", index + 1, transform_label(t))}</span>
+                            {
+                                match t {
+                                    Transform::Replace { pattern, replacement } => html! {
+                                        <>
+                                            <input type="text" placeholder="Выражение" value={pattern.clone()} oninput={edit(index, set_pattern)}/>
+                                            <input type="text" placeholder="Замена" value={replacement.clone()} oninput={edit(index, set_replacement)}/>
+                                        </>
+                                    },
+                                    Transform::Substring { start, length } => html! {
+                                        <>
+                                            <input type="number" placeholder="Начало" value={format!("{}", start)} oninput={edit(index, set_start)}/>
+                                            <input type="number" placeholder="Длина" value={length.map(|l| format!("{}", l)).unwrap_or_default()} oninput={edit(index, set_length)}/>
+                                        </>
+                                    },
+                                    _ => html! {},
+                                }
+                            }
+                            <span class="entry-buttons">
+                                <button class="delete-btn" onclick={remove(index)}>{"Удалить"}</button>
+                            </span>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+            <div class="transform-add">
+                <select onchange={on_kind}>
+                    <option value="trim" selected={*kind == "trim"}>{"Обрезать пробелы"}</option>
+                    <option value="collapse" selected={*kind == "collapse"}>{"Схлопнуть пробелы"}</option>
+                    <option value="replace" selected={*kind == "replace"}>{"Замена по выражению"}</option>
+                    <option value="upper" selected={*kind == "upper"}>{"Верхний регистр"}</option>
+                    <option value="lower" selected={*kind == "lower"}>{"Нижний регистр"}</option>
+                    <option value="thousands" selected={*kind == "thousands"}>{"Убрать разделители разрядов"}</option>
+                    <option value="substring" selected={*kind == "substring"}>{"Подстрока"}</option>
+                </select>
+                <button class="test-btn" onclick={on_add}>{"Добавить"}</button>
+            </div>
+        </div>
+    }
+}
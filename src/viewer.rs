@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use yew::prelude::*;
+
+use crate::parse::{OutputItem, OutputItemTabls, ScenItem};
+
+const COLORS: [&str; 8] = [
+    "#ffe08a", "#a8e6a1", "#9fd3ff", "#ffb3c1", "#d4b8ff", "#ffc99a", "#9ee8df", "#e0e0a0",
+];
+
+#[derive(Clone, PartialEq)]
+pub struct Mark {
+    pub start: usize,
+    pub end: usize,
+    pub label: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct TextViewerProps {
+    pub text: String,
+    pub marks: Vec<Mark>,
+    pub blocks: Vec<Mark>,
+}
+
+// Цвета раздаются полям по порядку появления; повторяются, только когда палитра кончилась
+fn palette(marks: &[Mark]) -> HashMap<&str, &'static str> {
+    let mut colors = HashMap::new();
+    for m in marks {
+        let next = COLORS[colors.len() % COLORS.len()];
+        colors.entry(m.label.as_str()).or_insert(next);
+    }
+    colors
+}
+
+// Собирает области подсветки из результата parse; совпадения в имени файла пропускаются
+pub fn collect_marks(
+    scens: &[ScenItem],
+    singles: &[OutputItem],
+    tables: &[OutputItemTabls],
+    marks: &mut Vec<Mark>,
+    blocks: &mut Vec<Mark>,
+) {
+    for item in singles {
+        if scens.get(item.scen_index).is_some_and(|s| s.file_name_bool) {
+            continue;
+        }
+        for span in item.spans.iter().flatten() {
+            marks.push(Mark {
+                start: span.start,
+                end: span.end,
+                label: item.name.clone(),
+            });
+        }
+    }
+    for table in tables {
+        blocks.push(Mark {
+            start: table.block.start,
+            end: table.block.end,
+            label: format!("Таблица: {}", table.names.join(",")),
+        });
+        for row in &table.spans {
+            for (name, span) in row {
+                if let Some(span) = span {
+                    marks.push(Mark {
+                        start: span.start,
+                        end: span.end,
+                        label: name.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+// Убирает пустые, пересекающиеся и не попадающие на границы символов области:
+// побеждает та, что началась раньше
+fn normalize(mut marks: Vec<Mark>, text: &str) -> Vec<Mark> {
+    marks.retain(|m| {
+        m.start < m.end
+            && m.end <= text.len()
+            && text.is_char_boundary(m.start)
+            && text.is_char_boundary(m.end)
+    });
+    marks.sort_by_key(|m| (m.start, std::cmp::Reverse(m.end)));
+    let mut out: Vec<Mark> = vec![];
+    for m in marks {
+        if out.last().is_some_and(|last| m.start < last.end) {
+            continue;
+        }
+        out.push(m);
+    }
+    out
+}
+
+fn render_marks(
+    text: &str,
+    start: usize,
+    end: usize,
+    marks: &[Mark],
+    colors: &HashMap<&str, &'static str>,
+) -> Html {
+    let mut nodes: Vec<Html> = vec![];
+    let mut pos = start;
+    for m in marks.iter().filter(|m| m.start >= start && m.end <= end) {
+        if m.start > pos {
+            nodes.push(html! { {&text[pos..m.start]} });
+        }
+        nodes.push(html! {
+            <mark style={format!("background: {};", colors.get(m.label.as_str()).unwrap_or(&COLORS[0]))} title={m.label.clone()}>
+                {&text[m.start..m.end]}
+            </mark>
+        });
+        pos = m.end;
+    }
+    if pos < end {
+        nodes.push(html! { {&text[pos..end]} });
+    }
+    nodes.into_iter().collect::<Html>()
+}
+
+#[function_component]
+pub fn TextViewer(props: &TextViewerProps) -> Html {
+    let text = props.text.as_str();
+    let colors = palette(&props.marks);
+    let blocks = normalize(props.blocks.clone(), text);
+    // поля, пересекающие границу таблицы, не подсвечиваются
+    let marks: Vec<Mark> = normalize(props.marks.clone(), text)
+        .into_iter()
+        .filter(|m| {
+            !blocks.iter().any(|b| {
+                (m.start < b.start && m.end > b.start) || (m.start < b.end && m.end > b.end)
+            })
+        })
+        .collect();
+
+    let mut nodes: Vec<Html> = vec![];
+    let mut pos = 0;
+    for b in &blocks {
+        if b.start > pos {
+            nodes.push(render_marks(text, pos, b.start, &marks, &colors));
+        }
+        nodes.push(html! {
+            <span class="table-block" title={b.label.clone()}>
+                {render_marks(text, b.start, b.end, &marks, &colors)}
+            </span>
+        });
+        pos = b.end;
+    }
+    nodes.push(render_marks(text, pos, text.len(), &marks, &colors));
+
+    html! {
+        <div class="viewer">{nodes.into_iter().collect::<Html>()}</div>
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::VecDeque;
+
+use gloo_timers::callback::Timeout;
+use gloo_worker::{Codec, HandlerId, Worker, WorkerScope};
+use serde::{Deserialize, Serialize};
+use web_sys::wasm_bindgen::JsValue;
+
+use json_editor_engine::{ParseJob, ParseOutput, RegexCache, ScenItem};
+
+#[derive(Serialize, Deserialize)]
+pub enum WorkerInput {
+    Submit {
+        job: u64,
+        // срочные задания (проверка формы, «Тест») встают в очередь перед пакетными
+        priority: bool,
+        file_name: String,
+        text: String,
+        scens: Vec<ScenItem>,
+    },
+    Cancel {
+        job: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum WorkerOutput {
+    Progress { job: u64, done: usize, total: usize },
+    Result { job: u64, output: ParseOutput },
+    Cancelled { job: u64 },
+}
+
+// Bincode не умеет serde_json::Value и untagged-перечисления, поэтому сообщения идут как JSON
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn encode<I>(input: I) -> JsValue
+    where
+        I: Serialize,
+    {
+        let text = serde_json::to_string(&input).expect("Не удалось закодировать сообщение");
+        JsValue::from_str(&text)
+    }
+
+    fn decode<O>(input: JsValue) -> O
+    where
+        O: for<'de> Deserialize<'de>,
+    {
+        let text = input.as_string().unwrap_or_default();
+        serde_json::from_str(&text).expect("Не удалось прочитать сообщение")
+    }
+}
+
+pub enum Msg {
+    Step,
+}
+
+struct Queued {
+    handler: HandlerId,
+    job: u64,
+    priority: bool,
+    parse_job: ParseJob,
+}
+
+pub struct ParseWorker {
+    queue: VecDeque<Queued>,
+    cache: RegexCache,
+    scheduled: bool,
+}
+
+impl ParseWorker {
+    // Шаг откладывается через setTimeout, чтобы между шагами успели прийти сообщения об отмене
+    fn schedule(&mut self, scope: &WorkerScope<Self>) {
+        if self.scheduled || self.queue.is_empty() {
+            return;
+        }
+        self.scheduled = true;
+        let scope = scope.clone();
+        Timeout::new(0, move || scope.send_message(Msg::Step)).forget();
+    }
+}
+
+impl Worker for ParseWorker {
+    type Message = Msg;
+    type Input = WorkerInput;
+    type Output = WorkerOutput;
+
+    fn create(_scope: &WorkerScope<Self>) -> Self {
+        ParseWorker {
+            queue: VecDeque::new(),
+            cache: RegexCache::new(),
+            scheduled: false,
+        }
+    }
+
+    fn update(&mut self, scope: &WorkerScope<Self>, _msg: Self::Message) {
+        self.scheduled = false;
+        let Some(front) = self.queue.front_mut() else {
+            return;
+        };
+        if !front.parse_job.is_finished() {
+            front.parse_job.step(&mut self.cache);
+            scope.respond(
+                front.handler,
+                WorkerOutput::Progress {
+                    job: front.job,
+                    done: front.parse_job.done(),
+                    total: front.parse_job.total(),
+                },
+            );
+        }
+        if front.parse_job.is_finished() {
+            if let Some(front) = self.queue.pop_front() {
+                scope.respond(
+                    front.handler,
+                    WorkerOutput::Result {
+                        job: front.job,
+                        output: front.parse_job.finish(),
+                    },
+                );
+            }
+        }
+        self.schedule(scope);
+    }
+
+    fn received(&mut self, scope: &WorkerScope<Self>, msg: Self::Input, id: HandlerId) {
+        match msg {
+            WorkerInput::Submit {
+                job,
+                priority,
+                file_name,
+                text,
+                scens,
+            } => {
+                let queued = Queued {
+                    handler: id,
+                    job,
+                    priority,
+                    parse_job: ParseJob::new(file_name, text, scens),
+                };
+                // начатое пакетное задание просто ждёт: ParseJob продолжится с того же шага
+                let position = if priority {
+                    self.queue.iter().take_while(|q| q.priority).count()
+                } else {
+                    self.queue.len()
+                };
+                self.queue.insert(position, queued);
+            }
+            WorkerInput::Cancel { job } => {
+                self.queue.retain(|q| !(q.handler == id && q.job == job));
+                scope.respond(id, WorkerOutput::Cancelled { job });
+            }
+        }
+        self.schedule(scope);
+    }
+
+    fn disconnected(&mut self, _scope: &WorkerScope<Self>, id: HandlerId) {
+        self.queue.retain(|q| q.handler != id);
+    }
+}
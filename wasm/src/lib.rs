@@ -0,0 +1,173 @@
+use json_editor_engine::lint::lint as lint_scens;
+use json_editor_engine::migrate::FORMAT_VERSION;
+use json_editor_engine::schema::{output_schema, project_schema, scen_item_schema};
+use json_editor_engine::{load_project, parse as parse_scens, ProjectFile, Scen, ScenItem};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+// Типы повторяют JSON-представление типов движка (см. также schema.rs)
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &str = r#"
+export type Json<T> = string & { readonly __json?: T };
+
+export type MatchMode = "First" | "Last" | { Nth: number } | "All";
+export type ValueType = "String" | "Integer" | "Decimal" | "Boolean" | "Date" | "DateTime";
+export type Requirement = "Required" | "Optional" | "InfoOnly";
+export type Transform =
+    | "Trim"
+    | "CollapseWhitespace"
+    | { Replace: { pattern: string; replacement: string } }
+    | "Uppercase"
+    | "Lowercase"
+    | "StripThousands"
+    | { Substring: { start: number; length: number | null } };
+
+export interface FieldSpec {
+    name: string;
+    value_type?: ValueType;
+    format?: string | null;
+    requirement?: Requirement;
+}
+
+export interface ScenItem {
+    regex?: string;
+    alternatives?: string[];
+    names?: string[];
+    file_name_bool?: boolean;
+    table?: boolean;
+    position?: number;
+    table_mask?: string | null;
+    mode?: MatchMode;
+    transforms?: Transform[];
+    fields?: FieldSpec[];
+}
+
+export interface ProjectMeta {
+    description?: string;
+    saved_at?: string | null;
+}
+
+export interface Scen {
+    version?: number;
+    file_name?: string;
+    text?: string;
+    scen?: ScenItem[] | null;
+    meta?: ProjectMeta;
+}
+
+export type Value = string | number | boolean | null;
+
+export interface Span {
+    start: number;
+    end: number;
+    line: number;
+    column: number;
+}
+
+export interface OutputItem {
+    name: string;
+    value: Value | Value[];
+    scen_index: number;
+    spans: (Span | null)[];
+    alternative: number;
+}
+
+export interface OutputItemTabls {
+    names: string[];
+    rows: Record<string, Value>[];
+    scen_index: number;
+    block: Span;
+    spans: Record<string, Span | null>[];
+    alternative: number;
+}
+
+export type ErrorEnum = "Warning" | "Error" | "Info";
+export type ErrorCode =
+    | "InvalidRegex"
+    | "InvalidReplaceRegex"
+    | "InvalidTableMask"
+    | "MissingTableMask"
+    | "NoMatch"
+    | "MissingGroup"
+    | "TablePositionOutOfRange"
+    | "CoercionFailed"
+    | "UnknownGroup"
+    | "EmptyName"
+    | "UnusedGroup"
+    | "UnknownField";
+
+export interface ErrorItem {
+    message: string;
+    type_error: ErrorEnum;
+    code: ErrorCode;
+    scen_index: number;
+    field: string | null;
+    detail: string | null;
+}
+
+export type ParseOutput = [OutputItem[], OutputItemTabls[], ErrorItem[]];
+"#;
+
+fn load_scens(scenarios: &str) -> Result<Vec<ScenItem>, JsError> {
+    load_project(scenarios)
+        .map(|(project, _)| project.into_scens())
+        .map_err(|e| JsError::new(&format!("Сценарии не загружены: {}", e)))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, JsError> {
+    serde_json::to_string(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+// scenarios — проект (Scen) или список ScenItem; старые версии формата обновляются
+#[wasm_bindgen(unchecked_return_type = "Json<ParseOutput>")]
+pub fn parse(
+    #[wasm_bindgen(js_name = fileName)] file_name: String,
+    text: String,
+    #[wasm_bindgen(unchecked_param_type = "Json<Scen | ScenItem[]>")] scenarios: &str,
+) -> Result<String, JsError> {
+    to_json(&parse_scens(file_name, text, load_scens(scenarios)?))
+}
+
+#[wasm_bindgen(unchecked_return_type = "Json<ErrorItem[]>")]
+pub fn lint(
+    #[wasm_bindgen(unchecked_param_type = "Json<Scen | ScenItem[]>")] scenarios: &str,
+) -> Result<String, JsError> {
+    to_json(&lint_scens(&load_scens(scenarios)?))
+}
+
+// Приводит проект любой поддерживаемой версии к текущему формату
+#[wasm_bindgen(js_name = migrateProject, unchecked_return_type = "Json<Scen>")]
+pub fn migrate_project(
+    #[wasm_bindgen(unchecked_param_type = "Json<Scen | ScenItem[]>")] project: &str,
+) -> Result<String, JsError> {
+    let scen = match load_project(project) {
+        Ok((ProjectFile::Full(scen), _)) => scen,
+        Ok((ProjectFile::Scens(scens), _)) => Scen {
+            version: FORMAT_VERSION,
+            scen: Some(scens),
+            ..Scen::default()
+        },
+        Err(e) => return Err(JsError::new(&format!("Проект не загружен: {}", e))),
+    };
+    to_json(&scen)
+}
+
+#[wasm_bindgen(js_name = formatVersion)]
+pub fn format_version() -> u32 {
+    FORMAT_VERSION
+}
+
+#[wasm_bindgen(js_name = projectSchema)]
+pub fn project_schema_json() -> Result<String, JsError> {
+    to_json(&project_schema())
+}
+
+#[wasm_bindgen(js_name = scenItemSchema)]
+pub fn scen_item_schema_json() -> Result<String, JsError> {
+    to_json(&scen_item_schema())
+}
+
+#[wasm_bindgen(js_name = outputSchema)]
+pub fn output_schema_json() -> Result<String, JsError> {
+    to_json(&output_schema())
+}